@@ -35,6 +35,13 @@ impl Args {
             .map(std::string::String::as_str)
     }
 
+    /// Removes `flag` from the arguments, returning whether it was given.
+    pub fn take_flag(&mut self, flag: &str) -> bool {
+        let len = self.parts.len();
+        self.parts.retain(|part| part != flag);
+        self.parts.len() != len
+    }
+
     pub fn parts(&self) -> Vec<&str> {
         self.parts.iter().map(String::as_str).collect()
     }
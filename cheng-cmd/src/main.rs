@@ -1,3 +1,6 @@
+// Commands that only print are dispatched as `Ok(command())`.
+#![allow(clippy::unit_arg)]
+
 mod args;
 
 mod uci;
@@ -13,7 +16,10 @@ use std::io::{self, BufRead, Write};
 use std::ops::ControlFlow::{self, Break, Continue};
 use std::time::{Duration, Instant};
 
-use cheng::{Board, FromIntoFen, LegalMove, PseudoMove, Square};
+use cheng::{
+    Board, BoardMask, Epd, FromIntoFen, Game, PackedMove, Perft, PerftStats, PseudoMove, Square,
+    SAN,
+};
 use flimsybird::Evaluable;
 
 use rustyline::error::ReadlineError;
@@ -25,14 +31,15 @@ use franfish::{GoResult, SearchExit};
 
 #[derive(Default)]
 pub struct Context {
-    board: Board,
+    game: Game,
     timeout: Option<Duration>,
+    perft: Perft,
 }
 
 impl Context {
-    fn go_franfish(&self) -> GoResult {
+    fn go_franfish(&self) -> GoResult<'_> {
         let mut franfish = franfish::Franfish::new(franfish::NoDebugger, self.timeout);
-        franfish.go(&self.board)
+        franfish.go(self.game.board())
     }
 }
 
@@ -96,10 +103,15 @@ fn interpret(context: &mut Context, args: Args) -> Result<(), String> {
 
         // our protocol
         "goinfo" => goinfo(context).map_err(String::from),
-        "perft" => perft(context, args).map_err(String::from),
-        "perft-bisect" => perft_bisect(context, args).map_err(String::from),
+        "perft" => perft(context, args),
+        "perft-bisect" => perft_bisect(context, args),
         "fen" => fen(context, args),
         "feed" => feed(context, args),
+        "undo" => undo(context),
+        "redo" => redo(context),
+        "goto" => goto(context, args),
+        "moves" => Ok(moves(context)),
+        "pgn" => pgn(context, args),
         "ev" => Ok(evaluate(context)),
         "d" => Ok(display_board(context, args)),
         "dump-tables" => Ok(dump_tables()),
@@ -114,9 +126,8 @@ fn interpret(context: &mut Context, args: Args) -> Result<(), String> {
 }
 
 fn version() {
-    use cheng::movegen::{Bishop, Rook};
-    use std::mem::size_of_val;
-    use std::ptr::addr_of;
+    use cheng::movegen::{Bishop, BishopTable, Rook, RookTable};
+    use std::mem::size_of;
 
     const GIT_HASH: &str = env!("GIT_HASH");
     const GIT_DIRTY: &str = env!("GIT_DIRTY");
@@ -124,65 +135,66 @@ fn version() {
 
     let version = format!("{GIT_HASH}-{GIT_DIRTY}");
 
-    // SAFETY: This is safe because we don't actually care about its value.
-    // This was done using addr_of!() because rust emitted the warning tracked in
-    // https://github.com/rust-lang/rust/issues/114447
-    let rook_hash_size = size_of_val(unsafe { &*addr_of!(cheng::movegen::ROOK_MOVES) });
-    let bishop_hash_size = size_of_val(unsafe { &*addr_of!(cheng::movegen::BISHOP_MOVES) });
+    let rook_hash_size = size_of::<RookTable>();
+    let bishop_hash_size = size_of::<BishopTable>();
 
     println!("cheng-cmd - {version}");
     println!("Built: {DATE}");
-    println!("Rook hash size: {rook_hash_size} (nbits={})", Rook::nbits());
     println!(
-        "Bishop hash size: {bishop_hash_size} (nbits={})",
-        Bishop::nbits()
+        "Rook table size: {rook_hash_size} bytes ({} entries)",
+        Rook::table_size()
+    );
+    println!(
+        "Bishop table size: {bishop_hash_size} bytes ({} entries)",
+        Bishop::table_size()
     );
 }
 
 #[allow(clippy::needless_pass_by_value)]
 fn display_board(context: &mut Context, _args: Args) {
-    println!("{}", BoardDisplay(context.board.inner()));
-    println!("fen: {}", context.board.as_fen());
-    println!("result: {:?}", context.board.result());
+    println!("{}", BoardDisplay(context.game.board().inner()));
+    println!("fen: {}", context.game.board().as_fen());
+    println!("result: {:?}", context.game.board().result());
+
+    let claimable_draws = context.game.board().claimable_draws();
+    if !claimable_draws.is_empty() {
+        println!("claimable draws: {claimable_draws:?}");
+    }
 }
 
 #[allow(clippy::needless_pass_by_value)]
 fn fen(context: &mut Context, args: Args) -> Result<(), String> {
     let fen = args.join_from("fen", 1)?;
-    context.board = Board::from_fen(&fen).map_err(|err| format!("{err:?}"))?;
+    let board = Board::from_fen(&fen).map_err(|err| format!("{err:?}"))?;
+    context.game = Game::new(board);
     Ok(())
 }
 
-#[must_use]
-pub fn continue_<E>(_movement: &LegalMove, _nodes: usize) -> ControlFlow<E, ()> {
-    Continue(())
-}
-
-fn incremental_perft<E, F>(board: &Board, depth: usize, mut callback: F) -> Result<usize, E>
+/// Runs perft on `board`, calling `callback` with the nodes under each move
+/// until it breaks.
+fn incremental_perft<E, F>(
+    perft: &Perft,
+    board: &Board,
+    depth: usize,
+    mut callback: F,
+) -> Result<usize, E>
 where
-    F: FnMut(&LegalMove, usize) -> ControlFlow<E, ()>,
+    F: FnMut(&PackedMove, usize) -> ControlFlow<E, ()>,
 {
     if depth == 0 {
         return Ok(1);
     }
 
-    let moves = board.moves();
     let mut nodes = 0;
-    for movement in moves {
-        let mut clone = board.clone();
-        clone.feed(movement.clone());
-
-        let move_nodes = incremental_perft(&clone, depth - 1, continue_)?;
+    let control = perft.divide_with(board.inner(), depth, |movement, move_nodes| {
         nodes += move_nodes;
+        callback(&movement, move_nodes)
+    });
 
-        let control = callback(&movement, move_nodes);
-        match control {
-            Continue(()) => continue,
-            Break(e) => return Err(e),
-        }
+    match control {
+        Continue(()) => Ok(nodes),
+        Break(e) => Err(e),
     }
-
-    Ok(nodes)
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -197,30 +209,35 @@ fn batch(context: &mut Context, args: Args) -> Result<(), String> {
 
     for line in reader.lines() {
         let line = line.map_err(|x| format!("{x}"))?;
-        let fields: Vec<&str> = line.split(',').collect();
+        if line.trim().is_empty() {
+            continue;
+        }
 
-        let fen = &fields[1];
-        let continuation_str = &fields[2];
-        let continuation: Vec<_> = continuation_str.split(' ').collect();
+        let epd: Epd = line.parse().map_err(|x| format!("{x:?}"))?;
+        let parse_error = |x| format!("Failed to parse the moves of {line}: {x:?}");
+        let best_moves = epd.best_moves().map_err(parse_error)?;
+        let avoid_moves = epd.avoid_moves().map_err(parse_error)?;
 
-        log::info!("loading fen {fen} and continuation {continuation:?}");
-        context.board = Board::from_fen(fen).map_err(|x| format!("{x:?}"))?;
-        context.board.try_feed(continuation[0]).unwrap();
+        log::info!("loading epd {epd}");
+        context.game = Game::new(epd.board.clone());
 
         let GoResult { exit, movement } = context.go_franfish();
-        let Some(expected) = context.board.validate(continuation[1]) else {
-            return Err(format!(
-                "Failed to parse continuation: {continuation_str} is not a valid move for {fen}",
-            ));
-        };
-
-        if movement != expected {
-            let real_fen = context.board.as_fen();
+        let played = PseudoMove::from(&movement);
+        let failed = (!best_moves.is_empty() && !best_moves.contains(&played))
+            || avoid_moves.contains(&played);
+
+        if failed {
+            let name = epd
+                .id()
+                .map_or_else(|| context.game.board().as_fen(), String::from);
             let reason = match exit {
                 SearchExit::FullDepth => "FAILED",
                 SearchExit::Timeout => "TIMEOUT",
             };
-            println!("FEN {real_fen:>63} {reason:>7} (expected={expected}, got={movement})");
+            let bm = epd.operation("bm").unwrap_or_default().join(" ");
+            let am = epd.operation("am").unwrap_or_default().join(" ");
+            let got = SAN(&movement, context.game.board());
+            println!("EPD {name:>63} {reason:>7} (bm={bm}, am={am}, got={got})");
         }
     }
 
@@ -229,7 +246,7 @@ fn batch(context: &mut Context, args: Args) -> Result<(), String> {
 
 #[allow(clippy::unnecessary_wraps)]
 fn goinfo(context: &mut Context) -> Result<(), &'static str> {
-    let mut board_clone = context.board.clone();
+    let mut board_clone = context.game.board().clone();
     let (mv, _) = board_clone.evaluate();
     let mv = mv.unwrap();
     println!("info pv {mv}");
@@ -237,16 +254,36 @@ fn goinfo(context: &mut Context) -> Result<(), &'static str> {
 }
 
 #[allow(clippy::needless_pass_by_value)]
-fn perft(context: &mut Context, args: Args) -> Result<(), String> {
+fn perft(context: &mut Context, mut args: Args) -> Result<(), String> {
     let perft_start = Instant::now();
 
+    let with_stats = args.take_flag("--stats");
     let depth: usize = args.parse("depth", 1)?;
+    let board = context.game.board();
+
+    // The stats count the nodes too, so they replace the plain perft.
+    let stats = if with_stats {
+        let mut stats = PerftStats::default();
+        for (movement, move_stats) in context.perft.divide_stats(board.inner(), depth) {
+            println!("{movement}: {}", move_stats.nodes);
+            stats += move_stats;
+        }
+        if depth == 0 {
+            stats.nodes = 1;
+        }
+        Some(stats)
+    } else {
+        None
+    };
 
-    let total_nodes = incremental_perft(&context.board, depth, |movement, nodes| {
-        println!("{movement}: {nodes}");
-        Continue::<()>(())
-    })
-    .unwrap();
+    let total_nodes = match stats {
+        Some(stats) => stats.nodes,
+        None => incremental_perft(&context.perft, board, depth, |movement, nodes| {
+            println!("{movement}: {nodes}");
+            Continue::<()>(())
+        })
+        .unwrap(),
+    };
 
     let perft_end = Instant::now();
     let perft_duration = perft_end - perft_start;
@@ -257,47 +294,109 @@ fn perft(context: &mut Context, args: Args) -> Result<(), String> {
         total_nodes as f32 / perft_duration.as_secs_f32()
     );
 
+    if let Some(stats) = stats {
+        println!("captures: {}", stats.captures);
+        println!("en passant: {}", stats.en_passant);
+        println!("castles: {}", stats.castles);
+        println!("promotions: {}", stats.promotions);
+        println!("checks: {}", stats.checks);
+        println!("discovered checks: {}", stats.discovered_checks);
+        println!("double checks: {}", stats.double_checks);
+        println!("checkmates: {}", stats.checkmates);
+    }
+
     Ok(())
 }
 
 #[allow(clippy::needless_pass_by_value)]
 fn feed(context: &mut Context, args: Args) -> Result<(), String> {
-    let pseudomove: PseudoMove = args.parse::<PseudoMove>("move", 1)?;
+    let movement = args.as_str("move", 1)?;
 
     context
-        .board
-        .try_feed(pseudomove)
+        .game
+        .try_play(movement)
         .map_err(|err| format!("Invalid move: {err:?}"))
 }
 
+fn undo(context: &mut Context) -> Result<(), String> {
+    if context.game.undo() {
+        Ok(())
+    } else {
+        Err("No move to undo".to_string())
+    }
+}
+
+fn redo(context: &mut Context) -> Result<(), String> {
+    if context.game.redo() {
+        Ok(())
+    } else {
+        Err("No move to redo".to_string())
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn goto(context: &mut Context, args: Args) -> Result<(), String> {
+    let ply: usize = args.parse("ply", 1)?;
+
+    if context.game.goto(ply) {
+        Ok(())
+    } else {
+        Err(format!("The game has no ply {ply}"))
+    }
+}
+
+fn moves(context: &mut Context) {
+    let game = &context.game;
+    let moves = game.san_moves();
+    for (ply, san) in moves.iter().enumerate() {
+        let marker = if ply + 1 == game.ply() { "*" } else { "" };
+        print!("{san}{marker} ");
+    }
+    println!();
+
+    let variations = game.variations();
+    if variations.len() > 1 {
+        println!("variations: {}", variations.join(" "));
+    }
+}
+
+/// Prints the game as PGN, or saves it when given a path.
+#[allow(clippy::needless_pass_by_value)]
+fn pgn(context: &mut Context, args: Args) -> Result<(), String> {
+    let pgn = context.game.to_pgn().to_string();
+
+    if args.len() > 1 {
+        let path = args.join_from("path", 1)?;
+        std::fs::write(&path, pgn).map_err(|err| format!("{path}: {err}"))
+    } else {
+        print!("{pgn}");
+        Ok(())
+    }
+}
+
 fn evaluate(context: &mut Context) {
-    let mut binding = context.board.clone();
+    let mut binding = context.game.board().clone();
     let (best_move, evaluation) = binding.evaluate();
 
     if let Some(best_move) = best_move {
-        println!("{}", cheng::SAN(&best_move, &context.board));
+        println!("{}", cheng::SAN(&best_move, context.game.board()));
     }
 
     println!("evaluation: {evaluation}");
 }
 
+/// Prints the rook moves for every occupancy of each square, in an order
+/// that doesn't depend on the magics.
 fn dump_tables() {
+    use cheng::movegen::{PieceExt, Rook};
+
     for sq in Square::iter_all() {
         println!("{sq:?}");
-        let table_of_moves = unsafe { cheng::internal::ROOK_MOVES[sq.to_index()] };
-        let mut amount_zeros = 0;
-        for moves in &table_of_moves {
-            let value = u64::from(*moves);
-            if value == 0 {
-                amount_zeros += 1;
-            } else {
-                if amount_zeros > 0 {
-                    println!("\t{amount_zeros} zeros");
-                    amount_zeros = 0;
-                }
-
-                println!("\t{value:016X}");
-            }
+        let relevant_occupancy = Rook::relevant_occupancy(sq);
+        for i in 0..relevant_occupancy.variations() {
+            let occupancy = relevant_occupancy.variation(i);
+            let moves = Rook::threats(sq, occupancy, BoardMask::default());
+            println!("\t{:016X}", u64::from(moves));
         }
 
         println!();
@@ -346,8 +445,8 @@ fn bench_fen() {
     let fen = "8/k7/1NpP1K2/6B1/Pp2P1pp/1P4rr/1PpbNP2/5R2 w - - 0 1";
     let board = Board::from_fen(fen).unwrap();
     evaluate(&mut Context {
-        board,
-        timeout: None,
+        game: Game::new(board),
+        ..Context::default()
     });
     let after = Instant::now();
     let took = after - before;
@@ -361,7 +460,7 @@ mod ff {
 
     #[allow(clippy::unnecessary_wraps)]
     pub fn go(context: &mut Context) -> Result<(), String> {
-        let GoResult { movement, .. } = franfish::go(&context.board);
+        let GoResult { movement, .. } = franfish::go(context.game.board());
         println!("bestmove {movement}");
 
         Ok(())
@@ -369,7 +468,7 @@ mod ff {
 
     #[allow(clippy::unnecessary_wraps)]
     pub fn go_debug(context: &mut Context) -> Result<(), String> {
-        let GoResult { movement, .. } = franfish::go_debug(&context.board);
+        let GoResult { movement, .. } = franfish::go_debug(context.game.board());
         println!("bestmove {movement}");
 
         Ok(())
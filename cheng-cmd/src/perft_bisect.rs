@@ -3,7 +3,7 @@ use std::{
     ops::ControlFlow::{Break, Continue},
 };
 
-use cheng::{Board, FromIntoFen};
+use cheng::{Board, FromIntoFen, Perft};
 use uci::Engine;
 
 use crate::args::Args;
@@ -30,10 +30,10 @@ pub fn perft_bisect(context: &mut Context, args: Args) -> Result<(), String> {
 
     let stockfish = Engine::new("stockfish").map_err(|e| format!("{e}"))?;
     let mut depth_remaining = depth;
-    let mut board = context.board.clone();
+    let mut board = context.game.board().clone();
     stockfish.set_position(&board.as_fen()).unwrap();
 
-    while let Err(e) = perft_bisect_iteration(&stockfish, &board, depth_remaining) {
+    while let Err(e) = perft_bisect_iteration(&context.perft, &stockfish, &board, depth_remaining) {
         match e {
             PerftBisectErr::UnexpectedMove { movement } => {
                 println!("Unexpected move: {movement}");
@@ -60,12 +60,13 @@ pub fn perft_bisect(context: &mut Context, args: Args) -> Result<(), String> {
 }
 
 fn perft_bisect_iteration(
+    perft: &Perft,
     stockfish: &Engine,
     board: &Board,
     depth: usize,
 ) -> Result<(), PerftBisectErr> {
     let mut move_perft_table = perft_stockfish(stockfish, depth).unwrap();
-    let bisect_result = incremental_perft(board, depth, |movement, nodes| {
+    let bisect_result = incremental_perft(perft, board, depth, |movement, nodes| {
         let movement_str = format!("{movement}");
         let expected_nodes = move_perft_table.remove(&movement_str);
         if let Some(expected) = expected_nodes {
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use cheng::{Board, FromIntoFen};
+use cheng::{Board, FromIntoFen, Game};
 use flimsybird::{Evaluable, Evaluation};
 
 use crate::args::Args;
@@ -26,7 +26,7 @@ pub fn position(context: &mut Context, args: Args) -> Result<(), String> {
         value => return Err(format!("Expected 'position' instead of {value:?}")),
     }
 
-    context.board = match iter.next() {
+    let board = match iter.next() {
         Some("startpos") => Board::default(),
         Some("fen") => {
             let fen_parts: Vec<&str> = iter
@@ -48,10 +48,11 @@ pub fn position(context: &mut Context, args: Args) -> Result<(), String> {
         Vec::new()
     };
 
+    context.game = Game::new(board);
     for mv in moves {
         context
-            .board
-            .try_feed(mv.as_str())
+            .game
+            .try_play(mv.as_str())
             .map_err(|_| "received invalid move".to_string())?;
     }
 
@@ -71,7 +72,8 @@ pub fn go(context: &mut Context, args: Args) -> Result<(), String> {
 
     let _movetime: usize = movetime.parse().map_err(|_| "invalid wtime".to_string())?;
 
-    let (best_move, _) = context.board.evaluate();
+    let mut board = context.game.board().clone();
+    let (best_move, _) = board.evaluate();
 
     if let Some(best_move) = best_move {
         println!("bestmove {best_move}");
@@ -85,12 +87,12 @@ pub fn go(context: &mut Context, args: Args) -> Result<(), String> {
 }
 
 pub fn eval(context: &mut Context) {
-    flimsybird::board_static_evaluation::<flimsybird::UciTracer>(&context.board);
+    flimsybird::board_static_evaluation::<flimsybird::UciTracer>(context.game.board());
 
     let result = flimsybird::quiescense_search(
-        &context.board,
-        Evaluation::winner(context.board.turn().opposite()),
-        Evaluation::winner(context.board.turn()),
+        context.game.board(),
+        Evaluation::winner(context.game.board().turn().opposite()),
+        Evaluation::winner(context.game.board().turn()),
         flimsybird::params::QUIESCENSE_DEPTH,
     );
 
@@ -18,7 +18,6 @@ mod pieces {
 #[path = "src/sides.rs"]
 mod sides;
 
-use std::collections::{hash_map::Entry, HashMap};
 use std::io::{self, Write};
 use std::{fmt, fs};
 
@@ -155,19 +154,39 @@ fn write_sliding_piece_occupancy<P: SlidingPiece>(f: &mut fs::File, name: &str)
     Ok(())
 }
 
+/// Writes the magics, the bits of the hash of each square and where the
+/// moves of each square start in the table shared by all of them.
 fn write_sliding_piece_magic<P: SlidingPiece>(f: &mut fs::File, name: &str) -> io::Result<()> {
+    let bits: Vec<u32> = Square::iter_all()
+        .map(|square| P::relevant_occupancy(square).count())
+        .collect();
+
     writeln!(f, "#[allow(clippy::mistyped_literal_suffixes)]")?;
     writeln!(f, "pub const {name}_MAGICS: [u64; 64] = [")?;
 
     for square in Square::iter_all() {
-        let (mask, collisions) = find_magic::<P>(square).expect("Could not find magic");
-        let board_formatter = BoardFormatter(BoardMask::from(mask));
-        writeln!(f, "    {board_formatter}, // #{collisions} collisions",)?;
+        let magic = find_magic::<P>(square).expect("Could not find magic");
+        writeln!(f, "    {},", BoardFormatter(BoardMask::from(magic)))?;
     }
 
     writeln!(f, "];")?;
     writeln!(f)?;
 
+    writeln!(f, "pub const {name}_BITS: [u32; 64] = {bits:?};")?;
+    writeln!(f)?;
+
+    let mut offset = 0;
+    let mut offsets = Vec::with_capacity(64);
+    for nbits in bits {
+        offsets.push(offset);
+        offset += 1 << nbits;
+    }
+
+    writeln!(f, "pub const {name}_OFFSETS: [usize; 64] = {offsets:?};")?;
+    writeln!(f)?;
+    writeln!(f, "pub const {name}_TABLE_SIZE: usize = {offset};")?;
+    writeln!(f)?;
+
     Ok(())
 }
 
@@ -176,42 +195,45 @@ fn random_few_bits() -> u64 {
     rand::random::<u64>() & rand::random::<u64>() & rand::random::<u64>()
 }
 
-fn find_magic<P: SlidingPiece>(square: Square) -> Result<(u64, u32), ()> {
-    let basic_occupancy = P::relevant_occupancy(square);
-    let basic_occupancy_bits_set = basic_occupancy.count();
-
-    let mut occupancy_variations = vec![BoardMask::default(); 1 << P::NBITS];
-    let mut available_moves = vec![BoardMask::default(); 1 << P::NBITS];
-
-    for i in 0..(1 << basic_occupancy_bits_set) {
-        occupancy_variations[i] = basic_occupancy.variation(i);
-        available_moves[i] = P::moves(square, occupancy_variations[i]);
-    }
+/// Finds a magic that hashes every occupancy of `square` using as many bits
+/// as there are relevant squares, the minimum possible.
+fn find_magic<P: SlidingPiece>(square: Square) -> Result<u64, ()> {
+    let relevant_occupancy = P::relevant_occupancy(square);
+    let nbits = relevant_occupancy.count();
 
-    let mut collisions: u32;
+    let occupancy_variations: Vec<_> = (0..relevant_occupancy.variations())
+        .map(|i| relevant_occupancy.variation(i))
+        .collect();
+    let available_moves: Vec<_> = occupancy_variations
+        .iter()
+        .map(|&occupancy| P::moves(square, occupancy))
+        .collect();
 
-    'lfm: for _ in 0..999_999 {
-        collisions = 0;
+    let mut used: Vec<Option<BoardMask>> = vec![None; 1 << nbits];
 
+    for _ in 0..100_000_000 {
         let magic = random_few_bits();
-        let mut used: HashMap<usize, BoardMask> = HashMap::default();
-
-        for i in 0..(1 << basic_occupancy_bits_set) {
-            let hash = magic_hash(magic, occupancy_variations[i], P::NBITS);
-            match used.entry(hash) {
-                Entry::Occupied(ref v) => {
-                    if v.get() != &available_moves[i] {
-                        continue 'lfm;
-                    }
-                    collisions += 1;
-                }
-                Entry::Vacant(v) => {
-                    v.insert(available_moves[i]);
-                }
-            }
+
+        // Magics that don't spread the relevant squares over the highest
+        // bits hardly ever work.
+        let top_bits = u64::from(relevant_occupancy).wrapping_mul(magic) & 0xFF00_0000_0000_0000;
+        if top_bits.count_ones() < 6 {
+            continue;
         }
 
-        return Ok((magic, collisions));
+        used.fill(None);
+        let fits = occupancy_variations
+            .iter()
+            .zip(&available_moves)
+            .all(|(&occupancy, &moves)| {
+                let slot = &mut used[magic_hash(magic, occupancy, nbits)];
+                // Occupancies with the same moves may share a slot.
+                *slot.get_or_insert(moves) == moves
+            });
+
+        if fits {
+            return Ok(magic);
+        }
     }
 
     Err(())
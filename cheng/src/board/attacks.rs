@@ -0,0 +1,162 @@
+use crate::{
+    movegen::{self, Bishop, King, Knight, PieceExt, Rook},
+    BorkedBoard, Piece, Side, Square,
+};
+
+use super::BoardMask;
+
+/// The squares strictly between `a` and `b` if they share a rank, a file or a
+/// diagonal, and no squares otherwise.
+pub(super) fn between(a: Square, b: Square) -> BoardMask {
+    let empty = BoardMask::default();
+    let (a_mask, b_mask) = (BoardMask::from(a), BoardMask::from(b));
+
+    if Rook::threats(a, empty, empty).get(b) {
+        Rook::threats(a, b_mask, empty).only(Rook::threats(b, a_mask, empty))
+    } else if Bishop::threats(a, empty, empty).get(b) {
+        Bishop::threats(a, b_mask, empty).only(Bishop::threats(b, a_mask, empty))
+    } else {
+        empty
+    }
+}
+
+/// The whole line through `a` and `b`, both included, if they share a rank,
+/// a file or a diagonal, and no squares otherwise.
+pub(super) fn line(a: Square, b: Square) -> BoardMask {
+    let empty = BoardMask::default();
+    let ends = BoardMask::from(a).with(BoardMask::from(b));
+
+    if Rook::threats(a, empty, empty).get(b) {
+        Rook::threats(a, empty, empty)
+            .only(Rook::threats(b, empty, empty))
+            .with(ends)
+    } else if Bishop::threats(a, empty, empty).get(b) {
+        Bishop::threats(a, empty, empty)
+            .only(Bishop::threats(b, empty, empty))
+            .with(ends)
+    } else {
+        empty
+    }
+}
+
+/// Squares from which a pawn of `side` would attack `square`.
+#[inline]
+pub(super) fn pawn_attackers(side: Side, square: Square) -> BoardMask {
+    movegen::pawn_attacks(side.opposite(), BoardMask::from(square))
+}
+
+impl BorkedBoard {
+    #[inline]
+    fn occupancy(&self) -> BoardMask {
+        self.white_side.occupancy.with(self.black_side.occupancy)
+    }
+
+    /// Pieces of `side` attacking `square`.
+    #[inline]
+    #[must_use]
+    pub fn attackers_to(&self, square: Square, side: Side) -> BoardMask {
+        self.attackers_to_with_occupancy(square, side, self.occupancy())
+    }
+
+    /// Pieces of `side` attacking `square` if the board had `occupancy`.
+    /// Pieces not in `occupancy` are considered captured.
+    #[must_use]
+    pub fn attackers_to_with_occupancy(
+        &self,
+        square: Square,
+        side: Side,
+        occupancy: BoardMask,
+    ) -> BoardMask {
+        let pieces = &self.side(side).pieces;
+        let empty = BoardMask::default();
+
+        let rooks = pieces.piece(Piece::Rook).with(pieces.piece(Piece::Queen));
+        let bishops = pieces.piece(Piece::Bishop).with(pieces.piece(Piece::Queen));
+
+        pawn_attackers(side, square)
+            .only(pieces.piece(Piece::Pawn))
+            .with(Knight::threats(square, empty, empty).only(pieces.piece(Piece::Knight)))
+            .with(King::threats(square, empty, empty).only(pieces.piece(Piece::King)))
+            .with(Rook::threats(square, occupancy, empty).only(rooks))
+            .with(Bishop::threats(square, occupancy, empty).only(bishops))
+            .only(occupancy)
+    }
+
+    /// Pieces giving check to the side to move.
+    #[must_use]
+    pub fn checkers(&self) -> BoardMask {
+        match self.side(self.turn).king_square() {
+            Some(king) => self.attackers_to(king, self.turn.opposite()),
+            None => BoardMask::default(),
+        }
+    }
+
+    /// Pieces of `side` that can't leave the line between their king and an
+    /// opposite slider without exposing the king.
+    #[must_use]
+    pub fn pinned(&self, side: Side) -> BoardMask {
+        match self.side(side).king_square() {
+            Some(king) => self.single_blockers(king, side.opposite(), side),
+            None => BoardMask::default(),
+        }
+    }
+
+    /// Pieces of `side` that would give check by discovery if they moved off
+    /// the line between one of their sliders and the opposite king.
+    #[must_use]
+    pub fn discovered_check_candidates(&self, side: Side) -> BoardMask {
+        match self.side(side.opposite()).king_square() {
+            Some(king) => self.single_blockers(king, side, side),
+            None => BoardMask::default(),
+        }
+    }
+
+    /// Sliders of `side` that would attack `square` if the first piece in
+    /// their way, of any side, was removed. Sliders already attacking
+    /// `square` aren't included.
+    #[must_use]
+    pub fn xray_attacks(&self, square: Square, side: Side) -> BoardMask {
+        let pieces = &self.side(side).pieces;
+        let occupancy = self.occupancy();
+        let empty = BoardMask::default();
+
+        let rooks = pieces.piece(Piece::Rook).with(pieces.piece(Piece::Queen));
+        let bishops = pieces.piece(Piece::Bishop).with(pieces.piece(Piece::Queen));
+
+        let rook_attacks = Rook::threats(square, occupancy, empty);
+        let bishop_attacks = Bishop::threats(square, occupancy, empty);
+        let rook_xrays = Rook::threats(square, occupancy.without(rook_attacks), empty);
+        let bishop_xrays = Bishop::threats(square, occupancy.without(bishop_attacks), empty);
+
+        rook_xrays
+            .without(rook_attacks)
+            .only(rooks)
+            .with(bishop_xrays.without(bishop_attacks).only(bishops))
+    }
+
+    /// Pieces of `blockers` that are the only piece between `target` and a
+    /// slider of `sliders` aiming at it.
+    fn single_blockers(&self, target: Square, sliders: Side, blockers: Side) -> BoardMask {
+        let pieces = &self.side(sliders).pieces;
+        let occupancy = self.occupancy();
+        let empty = BoardMask::default();
+
+        let queens = pieces.piece(Piece::Queen);
+        let snipers = Rook::threats(target, empty, empty)
+            .only(pieces.piece(Piece::Rook).with(queens))
+            .with(
+                Bishop::threats(target, empty, empty)
+                    .only(pieces.piece(Piece::Bishop).with(queens)),
+            );
+
+        let mut result = BoardMask::default();
+        for sniper in snipers {
+            let in_between = between(target, sniper).only(occupancy);
+            if in_between.count() == 1 {
+                result = result.with(in_between);
+            }
+        }
+
+        result.only(self.side(blockers).occupancy)
+    }
+}
@@ -1,9 +1,11 @@
 use crate::{
-    board::BoardMask, side_state::SideState, GameResult, LegalMove, Piece, PseudoMove,
-    PseudoMoveGenerator, Side, SidedPiece,
+    board::{BoardMask, DrawReason},
+    side_state::SideState,
+    zobrist, GameResult, LegalMove, Piece, PseudoMove, PseudoMoveGenerator, Side, SidedPiece,
+    Square, TryIntoMove,
 };
 
-use super::TryFeedError;
+use super::{attacks::pawn_attackers, TryFeedError};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BorkedBoard {
@@ -12,6 +14,9 @@ pub struct BorkedBoard {
     pub turn: Side,
     pub halfmove_clock: usize,
     pub fullmove_clock: usize,
+    /// Whether castles are encoded as the king taking its own rook, as done
+    /// in Chess960.
+    pub chess960: bool,
 }
 
 impl Default for BorkedBoard {
@@ -31,6 +36,7 @@ impl BorkedBoard {
             turn: Side::White,
             halfmove_clock: 0,
             fullmove_clock: 1,
+            chess960: false,
         }
     }
 
@@ -51,6 +57,47 @@ impl BorkedBoard {
         }
     }
 
+    /// Zobrist key of the position, maintained incrementally as moves are fed.
+    /// The en passant square is only hashed when a pawn can capture on it.
+    #[inline]
+    #[must_use]
+    pub fn hash(&self) -> u64 {
+        self.white_side.hash ^ self.black_side.hash ^ self.turn_hash()
+    }
+
+    /// Computes the Zobrist key of the position from scratch. This should
+    /// always match [`BorkedBoard::hash`].
+    #[must_use]
+    pub fn compute_hash(&self) -> u64 {
+        self.white_side.compute_hash() ^ self.black_side.compute_hash() ^ self.turn_hash()
+    }
+
+    /// The part of the hash that depends on the side to move.
+    #[inline]
+    fn turn_hash(&self) -> u64 {
+        let turn = match self.turn {
+            Side::White => 0,
+            Side::Black => zobrist::black_to_move(),
+        };
+
+        match self.capturable_en_passant() {
+            Some(square) => turn ^ zobrist::en_passant(square),
+            None => turn,
+        }
+    }
+
+    /// The en passant square, if a pawn of the side to move attacks it. The
+    /// capture may still be illegal.
+    #[inline]
+    pub(crate) fn capturable_en_passant(&self) -> Option<Square> {
+        let square = self.side(self.turn.opposite()).en_passant?;
+        let pawns = self.side(self.turn).pieces.piece(Piece::Pawn);
+
+        pawn_attackers(self.turn, square)
+            .has_coincidences(pawns)
+            .then_some(square)
+    }
+
     #[inline]
     #[must_use]
     pub fn is_borked(&self) -> bool {
@@ -80,8 +127,8 @@ impl BorkedBoard {
         let mut opposite = self.side(self.turn.opposite()).occupancy;
 
         if piece == Piece::King {
-            if let Some(c) = crate::movement::Castle::move_could_be_castle(self.turn, &pseudomove) {
-                return self.side(self.turn).castling_rights.contains(c);
+            if let Some(castle) = self.castle_from_move(&pseudomove) {
+                return self.can_castle(self.turn, castle);
             }
         } else if piece == Piece::Pawn {
             opposite = match self.side(self.turn.opposite()).en_passant {
@@ -98,9 +145,9 @@ impl BorkedBoard {
 
     pub fn try_feed<M>(&mut self, movement: M) -> Result<(), TryFeedError<M::Error>>
     where
-        M: TryInto<PseudoMove>,
+        M: TryIntoMove,
     {
-        let movement = match movement.try_into() {
+        let movement = match movement.try_into_move(self) {
             Ok(movement) => movement,
             Err(err) => return Err(TryFeedError::Parsing(err)),
         };
@@ -131,10 +178,8 @@ impl BorkedBoard {
         {
             // En passant capture
             let side = self.side_mut(self.turn.opposite());
-            let pawn_pieces = side.pieces.piece_mut(Piece::Pawn);
             let actual_pawn_square = movement.destination.next_rank(side.side);
-            side.occupancy.reset(actual_pawn_square);
-            pawn_pieces.reset(actual_pawn_square);
+            side.remove(actual_pawn_square);
         }
 
         // This handles en passant capture as well.
@@ -153,8 +198,11 @@ impl BorkedBoard {
             self.fullmove_clock += 1;
         }
 
-        self.side_mut(self.turn.opposite())
-            .remove(movement.destination);
+        let opposite_side = self.side_mut(self.turn.opposite());
+        opposite_side.remove(movement.destination);
+        // The opposite side can no longer be captured en passant, so forget
+        // about it to keep the position (and its hash) canonical.
+        opposite_side.set_en_passant(None);
 
         self.white_side.update_threats(&self.black_side);
         self.black_side.update_threats(&self.white_side);
@@ -163,34 +211,66 @@ impl BorkedBoard {
         self.black_side.update_king_in_check(&self.white_side);
 
         self.turn = self.turn.opposite();
+
+        debug_assert_eq!(self.hash(), self.compute_hash());
     }
 
+    /// Computes the result of the game, taking into account only the rules
+    /// that end the game automatically.
     pub fn compute_result(&self) -> GameResult {
         debug_assert!(!self.is_borked());
 
-        if self.halfmove_clock >= 100 {
-            return GameResult::Draw;
+        if self.is_insufficient_material() {
+            return GameResult::Draw {
+                reason: DrawReason::InsufficientMaterial,
+            };
         }
 
-        for pseudomove in self.moves() {
-            let mut clone = self.clone();
-            clone.feed_unchecked(&pseudomove);
-            if !clone.is_borked() {
-                return GameResult::Undecided;
-            }
+        if self.is_dead_position() {
+            return GameResult::Draw {
+                reason: DrawReason::DeadPosition,
+            };
         }
 
-        if self.side(self.turn).king_in_check {
+        let legal_move_exists = !self.legal_moves().is_empty();
+
+        if legal_move_exists {
+            // Checkmate takes precedence over the seventy-five-move rule.
+            if self.halfmove_clock >= 150 {
+                GameResult::Draw {
+                    reason: DrawReason::SeventyFiveMove,
+                }
+            } else {
+                GameResult::Undecided
+            }
+        } else if self.side(self.turn).king_in_check {
             GameResult::Checkmate {
                 winner: self.turn.opposite(),
             }
         } else {
-            GameResult::Draw
+            GameResult::Draw {
+                reason: DrawReason::Stalemate,
+            }
+        }
+    }
+
+    pub fn perft(&mut self, depth: usize) -> usize {
+        if depth == 0 {
+            return 1;
         }
+
+        let mut nodes = 0;
+        for movement in self.legal_moves() {
+            let undo = self.make_packed(movement);
+            nodes += self.perft(depth - 1);
+            self.unmake(undo);
+        }
+
+        nodes
     }
 
     #[must_use]
-    pub fn moves(&self) -> PseudoMoveGenerator {
+    pub fn moves(&self) -> PseudoMoveGenerator<'_> {
         PseudoMoveGenerator::new(self)
     }
 
@@ -0,0 +1,121 @@
+use crate::{
+    movement::MoveKind, side_state::CastlingRights, BorkedBoard, Castle, Piece, PseudoMove, Side,
+    Square,
+};
+
+use super::BoardMask;
+
+/// The squares between `a` and `b`, both included. They must be on the same
+/// rank.
+fn span(a: Square, b: Square) -> BoardMask {
+    let (low, high) = if a.to_index() < b.to_index() {
+        (a.to_index(), b.to_index())
+    } else {
+        (b.to_index(), a.to_index())
+    };
+
+    BoardMask::from((u64::MAX >> (63 - high)) & (u64::MAX << low))
+}
+
+impl BorkedBoard {
+    /// Whether `side` can castle, ignoring whether the king ends up in check.
+    ///
+    /// This follows the Chess960 rules, which reduce to the standard ones when
+    /// the king and the rooks start on their usual squares: every square the
+    /// king and the rook go through must be empty (except for themselves) and
+    /// the king can't be in check nor go through a threatened square.
+    #[must_use]
+    pub fn can_castle(&self, side: Side, castle: Castle) -> bool {
+        let state = self.side(side);
+        let opposite = self.side(side.opposite());
+
+        if !state.castling_rights.contains(castle) || state.king_in_check {
+            return false;
+        }
+
+        let Some(king) = state.king_square() else {
+            return false;
+        };
+        let rook = state.castling_rook_square(castle);
+        if !state.pieces.piece(Piece::Rook).get(rook) {
+            return false;
+        }
+
+        let king_path = span(king, castle.king_square_after_castle(side));
+        let rook_path = span(rook, castle.rook_square_after_castle(side));
+
+        let occupancy = state
+            .occupancy
+            .with(opposite.occupancy)
+            .without(BoardMask::from(king))
+            .without(BoardMask::from(rook));
+
+        !king_path.with(rook_path).has_coincidences(occupancy)
+            && !king_path.has_coincidences(opposite.threats)
+    }
+
+    /// The castle as it is encoded in this board: the king moving two squares
+    /// in standard chess and the king taking its own rook in Chess960.
+    #[must_use]
+    pub fn castle_move(&self, side: Side, castle: Castle) -> PseudoMove {
+        let state = self.side(side);
+        let origin = state
+            .king_square()
+            .unwrap_or(Castle::standard_king_square(side));
+
+        let destination = if self.chess960 {
+            state.castling_rook_square(castle)
+        } else {
+            castle.king_square_after_castle(side)
+        };
+
+        PseudoMove {
+            origin,
+            destination,
+            kind: MoveKind::Castle(castle),
+        }
+    }
+
+    /// Whether castling can't be played with the standard chess rules because
+    /// a side that can still castle has its king or castling rook elsewhere.
+    #[must_use]
+    pub fn needs_chess960(&self) -> bool {
+        [&self.white_side, &self.black_side]
+            .into_iter()
+            .any(|state| {
+                let king_moved = state.castling_rights != CastlingRights::None
+                    && state.king_square() != Some(Castle::standard_king_square(state.side));
+
+                let rook_moved = [Castle::KingSide, Castle::QueenSide]
+                    .into_iter()
+                    .any(|castle| {
+                        state.castling_rights.contains(castle)
+                            && state.castling_rook_square(castle)
+                                != castle.standard_rook_square(state.side)
+                    });
+
+                king_moved || rook_moved
+            })
+    }
+
+    /// Recognises castles written with any of the two encodings, as long as
+    /// the side to move still has the right to castle. Moving the king two
+    /// squares is only a castle in standard chess.
+    #[must_use]
+    pub fn castle_from_move(&self, movement: &PseudoMove) -> Option<Castle> {
+        let state = self.side(self.turn);
+        if state.king_square() != Some(movement.origin) {
+            return None;
+        }
+
+        [Castle::KingSide, Castle::QueenSide]
+            .into_iter()
+            .filter(|&castle| state.castling_rights.contains(castle))
+            .find(|&castle| {
+                movement.destination == state.castling_rook_square(castle)
+                    || (!self.chess960
+                        && movement.origin == Castle::standard_king_square(self.turn)
+                        && movement.destination == castle.king_square_after_castle(self.turn))
+            })
+    }
+}
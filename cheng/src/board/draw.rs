@@ -0,0 +1,117 @@
+use crate::{
+    board::BoardMask,
+    movegen::{self, King, PieceExt},
+    BorkedBoard, Piece, Side,
+};
+
+const DARK_SQUARES: BoardMask = BoardMask::const_from(0xAA55_AA55_AA55_AA55);
+
+impl BorkedBoard {
+    /// Whether neither side has enough material left to checkmate: K vs K,
+    /// K+N vs K, K+B vs K, or any number of bishops all on the same colour.
+    #[must_use]
+    pub fn is_insufficient_material(&self) -> bool {
+        let mut knights = 0;
+        let mut bishops = BoardMask::default();
+
+        for side in [Side::White, Side::Black] {
+            let pieces = &self.side(side).pieces;
+            let heavy_or_pawns = pieces
+                .piece(Piece::Pawn)
+                .with(pieces.piece(Piece::Rook))
+                .with(pieces.piece(Piece::Queen));
+
+            if heavy_or_pawns != BoardMask::default() {
+                return false;
+            }
+
+            knights += pieces.piece(Piece::Knight).count();
+            bishops = bishops.with(pieces.piece(Piece::Bishop));
+        }
+
+        let minor_pieces = knights + bishops.count();
+        let bishops_on_same_colour = !bishops.has_coincidences(DARK_SQUARES)
+            || bishops.without(DARK_SQUARES) == BoardMask::default();
+
+        minor_pieces <= 1 || (knights == 0 && bishops_on_same_colour)
+    }
+
+    /// A conservative dead position detector: only kings and pawns are left,
+    /// every pawn is blocked, no pawn can capture and neither king can reach
+    /// an enemy pawn. Returns `false` whenever it isn't sure.
+    #[must_use]
+    pub fn is_dead_position(&self) -> bool {
+        let white_pawns = self.white_side.pieces.piece(Piece::Pawn);
+        let black_pawns = self.black_side.pieces.piece(Piece::Pawn);
+        let pawns = white_pawns.with(black_pawns);
+
+        for side in [Side::White, Side::Black] {
+            let side_state = self.side(side);
+            let pawns_and_king = side_state
+                .pieces
+                .piece(Piece::Pawn)
+                .with(side_state.pieces.piece(Piece::King));
+
+            if side_state.occupancy != pawns_and_king || side_state.en_passant.is_some() {
+                return false;
+            }
+        }
+
+        if pawns == BoardMask::default() {
+            return false;
+        }
+
+        for side in [Side::White, Side::Black] {
+            let opposite_pawns = self.side(side.opposite()).pieces.piece(Piece::Pawn);
+
+            for square in self.side(side).pieces.piece(Piece::Pawn) {
+                let is_blocked = square
+                    .checked_next_rank(side)
+                    .is_some_and(|next| pawns.get(next));
+                let can_capture =
+                    movegen::pawn_threats(side, square).has_coincidences(opposite_pawns);
+
+                if !is_blocked || can_capture {
+                    return false;
+                }
+            }
+        }
+
+        !self.king_can_reach_enemy_pawn(Side::White) && !self.king_can_reach_enemy_pawn(Side::Black)
+    }
+
+    fn king_can_reach_enemy_pawn(&self, side: Side) -> bool {
+        let own_pawns = self.side(side).pieces.piece(Piece::Pawn);
+        let opposite_pawns = self.side(side.opposite()).pieces.piece(Piece::Pawn);
+
+        let mut forbidden = own_pawns;
+        for square in opposite_pawns {
+            forbidden = forbidden.with(movegen::pawn_threats(side.opposite(), square));
+        }
+
+        // Flood fill every square the king could walk to, ignoring the other
+        // king. That only makes the result more conservative.
+        let mut reachable = self.side(side).pieces.piece(Piece::King);
+        let mut frontier = reachable;
+        while frontier != BoardMask::default() {
+            let mut next = BoardMask::default();
+            for square in frontier {
+                next = next.with(King::threats(
+                    square,
+                    BoardMask::default(),
+                    BoardMask::default(),
+                ));
+            }
+
+            let next = next.without(forbidden).without(reachable);
+            if next.has_coincidences(opposite_pawns) {
+                return true;
+            }
+
+            reachable = reachable.with(next);
+            frontier = next;
+        }
+
+        false
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    movegen::{self, King, PieceExt},
+    BorkedBoard, Castle, MoveList, PackedMove, Piece, SidedPiece, Square,
+};
+
+use super::{
+    attacks::{between, line},
+    movegen::push_pawn_moves,
+    BoardMask,
+};
+
+/// What restricts the moves of the side to move, computed once per position.
+struct Constraints {
+    king: Square,
+    checkers: BoardMask,
+    /// Squares the pieces other than the king have to move to: the checker
+    /// and the squares between it and the king when in check.
+    check_mask: BoardMask,
+    /// Pieces that can only move along the line through them and the king.
+    pinned: BoardMask,
+}
+
+impl BorkedBoard {
+    fn constraints(&self, king: Square) -> Constraints {
+        let checkers = self.checkers();
+        let check_mask = match checkers.first() {
+            None => BoardMask::from(u64::MAX),
+            Some(checker) => between(king, checker).with(checkers),
+        };
+
+        Constraints {
+            king,
+            checkers,
+            check_mask,
+            pinned: self.pinned(self.turn),
+        }
+    }
+
+    /// Generates the legal moves of the side to move, without trying them on
+    /// the board.
+    #[must_use]
+    pub fn legal_moves(&self) -> MoveList {
+        let mut moves = MoveList::new();
+        // Positions without a king (only built by hand) have no legal moves.
+        if let Some(king) = self.side(self.turn).king_square() {
+            self.generate_legal_moves(&self.constraints(king), &mut moves);
+        }
+        moves
+    }
+
+    fn generate_legal_moves(&self, constraints: &Constraints, moves: &mut MoveList) {
+        let us = self.turn;
+        let ours = self.side(us);
+        let theirs = self.side(us.opposite());
+        let double_check = constraints.checkers.count() > 1;
+
+        for piece in Piece::iter() {
+            if piece == Piece::King {
+                self.generate_legal_king_moves(constraints, moves);
+                continue;
+            }
+
+            if double_check {
+                continue;
+            }
+
+            if piece == Piece::Pawn {
+                self.generate_legal_pawn_moves(constraints, moves);
+                continue;
+            }
+
+            for origin in ours.pieces.piece(piece) {
+                let mut destinations = movegen::moves(
+                    SidedPiece(us, piece),
+                    origin,
+                    ours.occupancy,
+                    theirs.occupancy,
+                )
+                .only(constraints.check_mask);
+
+                if constraints.pinned.get(origin) {
+                    destinations = destinations.only(line(constraints.king, origin));
+                }
+
+                for destination in destinations {
+                    moves.push(self.pack_piece_move(origin, destination));
+                }
+            }
+        }
+
+        if constraints.checkers == BoardMask::default() {
+            self.generate_legal_castles(constraints, moves);
+        }
+    }
+
+    #[inline]
+    fn pack_piece_move(&self, origin: Square, destination: Square) -> PackedMove {
+        if self.side(self.turn.opposite()).occupancy.get(destination) {
+            PackedMove::capture(origin, destination)
+        } else {
+            PackedMove::quiet(origin, destination)
+        }
+    }
+
+    fn generate_legal_king_moves(&self, constraints: &Constraints, moves: &mut MoveList) {
+        let us = self.turn;
+        let ours = self.side(us);
+        let theirs = self.side(us.opposite());
+        let king = constraints.king;
+
+        // The king can't hide from a slider by stepping back along its ray.
+        let occupancy = ours
+            .occupancy
+            .with(theirs.occupancy)
+            .without(BoardMask::from(king));
+
+        for destination in King::moves(king, ours.occupancy, theirs.occupancy) {
+            let attackers = self.attackers_to_with_occupancy(destination, us.opposite(), occupancy);
+            if attackers == BoardMask::default() {
+                moves.push(self.pack_piece_move(king, destination));
+            }
+        }
+    }
+
+    /// Unpinned pawns are generated all at once, pinned ones can only move
+    /// along the pin.
+    fn generate_legal_pawn_moves(&self, constraints: &Constraints, moves: &mut MoveList) {
+        let us = self.turn;
+        let pawns = self.side(us).pieces.piece(Piece::Pawn);
+
+        push_pawn_moves(
+            self,
+            us,
+            pawns.without(constraints.pinned),
+            constraints.check_mask,
+            false,
+            moves,
+        );
+
+        for origin in pawns.only(constraints.pinned) {
+            let targets = constraints.check_mask.only(line(constraints.king, origin));
+            push_pawn_moves(self, us, BoardMask::from(origin), targets, false, moves);
+        }
+
+        for origin in pawns {
+            self.generate_legal_en_passant(constraints, origin, moves);
+        }
+    }
+
+    /// En passant removes two pieces from the same rank, which the pins don't
+    /// account for, so the resulting position is checked directly.
+    fn generate_legal_en_passant(
+        &self,
+        constraints: &Constraints,
+        origin: Square,
+        moves: &mut MoveList,
+    ) {
+        let us = self.turn;
+        let theirs = self.side(us.opposite());
+
+        let Some(destination) = theirs.en_passant else {
+            return;
+        };
+        if !movegen::pawn_threats(us, origin).get(destination) {
+            return;
+        }
+
+        let captured = destination.next_rank(theirs.side);
+        let occupancy = self
+            .side(us)
+            .occupancy
+            .with(theirs.occupancy)
+            .without(BoardMask::from(origin))
+            .without(BoardMask::from(captured))
+            .with(BoardMask::from(destination));
+
+        let attackers =
+            self.attackers_to_with_occupancy(constraints.king, us.opposite(), occupancy);
+        if attackers == BoardMask::default() {
+            moves.push(PackedMove::en_passant(origin, destination));
+        }
+    }
+
+    fn generate_legal_castles(&self, constraints: &Constraints, moves: &mut MoveList) {
+        let us = self.turn;
+        let occupancy = self
+            .side(us)
+            .occupancy
+            .with(self.side(us.opposite()).occupancy);
+
+        for castle in [Castle::QueenSide, Castle::KingSide] {
+            if !self.can_castle(us, castle) {
+                continue;
+            }
+
+            // In Chess960 the rook may have been shielding the king's
+            // destination, so check it once the rook has moved.
+            let king_after = castle.king_square_after_castle(us);
+            let occupancy = occupancy
+                .without(BoardMask::from(constraints.king))
+                .without(BoardMask::from(self.side(us).castling_rook_square(castle)))
+                .with(BoardMask::from(king_after))
+                .with(BoardMask::from(castle.rook_square_after_castle(us)));
+
+            let attackers = self.attackers_to_with_occupancy(king_after, us.opposite(), occupancy);
+            if attackers == BoardMask::default() {
+                let movement = self.castle_move(us, castle);
+                moves.push(PackedMove::castle(
+                    movement.origin,
+                    movement.destination,
+                    castle,
+                ));
+            }
+        }
+    }
+}
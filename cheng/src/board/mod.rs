@@ -1,18 +1,34 @@
+mod attacks;
+
 mod borked;
 pub use borked::BorkedBoard;
 
+mod castling;
+
+mod draw;
+
 mod mask;
 pub use mask::BoardMask;
 
 mod iterator;
 
+mod legal;
+
 mod movegen;
-pub use movegen::{MoveGenerator, PseudoMoveGenerator};
+pub use movegen::{GenerationMode, MoveGenerator, PseudoMoveGenerator};
 
 mod parsing;
-pub use parsing::FENParsingError;
+pub use parsing::{
+    FENParsingError, FENParsingErrorKind, FenCastling, FenEnPassant, FenField, FenValidation,
+    FenWriteOptions,
+};
 
-use crate::{FromIntoFen, LegalMove, PseudoMove, Side};
+mod see;
+
+mod undo;
+pub use undo::Undo;
+
+use crate::{FromIntoFen, LegalMove, PerftStats, Side, TryIntoMove};
 
 use std::convert::TryFrom;
 
@@ -22,23 +38,59 @@ pub enum TryFeedError<E> {
     InvalidMove,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClaimDrawError {
+    GameOver,
+    NotClaimable,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GameResult {
     Undecided,
-    Draw,
+    Draw { reason: DrawReason },
     Checkmate { winner: Side },
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawReason {
+    Stalemate,
+    /// 100 halfmoves without captures or pawn moves. Must be claimed.
+    FiftyMove,
+    /// 150 halfmoves without captures or pawn moves.
+    SeventyFiveMove,
+    /// The same position three times. Must be claimed.
+    Threefold,
+    /// The same position five times.
+    Fivefold,
+    InsufficientMaterial,
+    DeadPosition,
+    Agreement,
+}
+
+impl DrawReason {
+    /// Whether the draw has to be claimed by a player instead of ending the
+    /// game as soon as it happens.
+    #[must_use]
+    pub fn is_claimable(self) -> bool {
+        matches!(self, Self::FiftyMove | Self::Threefold | Self::Agreement)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Board {
     inner: BorkedBoard,
     result: GameResult,
+    // Hashes of the positions since the last irreversible move, including the
+    // current one.
+    history: Vec<u64>,
 }
 
 impl Default for Board {
     fn default() -> Board {
+        let inner = BorkedBoard::from_fen(Board::DEFAULT_FEN).unwrap();
         Board {
-            inner: BorkedBoard::from_fen(Board::DEFAULT_FEN).unwrap(),
+            history: vec![inner.hash()],
+            inner,
             result: GameResult::Undecided,
         }
     }
@@ -54,6 +106,7 @@ impl TryFrom<BorkedBoard> for Board {
 
         let result = borked.compute_result();
         Ok(Board {
+            history: vec![borked.hash()],
             inner: borked,
             result,
         })
@@ -76,6 +129,19 @@ impl Board {
         self.inner.turn
     }
 
+    #[inline]
+    #[must_use]
+    pub fn is_chess960(&self) -> bool {
+        self.inner.chess960
+    }
+
+    /// Plays castles as in Chess960, the king taking its own rook. Positions
+    /// that can't be played with the standard rules (detected when parsing the
+    /// FEN) stay in Chess960 mode.
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.inner.chess960 = chess960 || self.inner.needs_chess960();
+    }
+
     #[inline]
     #[must_use]
     pub fn result(&self) -> GameResult {
@@ -85,56 +151,114 @@ impl Board {
     #[inline]
     pub fn try_feed<M>(&mut self, movement: M) -> Result<(), TryFeedError<M::Error>>
     where
-        M: TryInto<PseudoMove>,
+        M: TryIntoMove,
     {
         if self.result != GameResult::Undecided {
             return Err(TryFeedError::InvalidMove);
         }
 
         self.inner.try_feed(movement)?;
-        self.result = self.inner.compute_result();
+        self.update_after_feed();
         Ok(())
     }
 
     #[inline]
     pub fn feed(&mut self, movement: LegalMove) {
-        self.inner.feed_unchecked(&movement.into())
+        self.inner.feed_unchecked(&movement.into());
+        self.update_after_feed();
+    }
+
+    fn update_after_feed(&mut self) {
+        if self.inner.halfmove_clock == 0 {
+            self.history.clear();
+        }
+        self.history.push(self.inner.hash());
+
+        self.result = self.inner.compute_result();
+        if self.result == GameResult::Undecided && self.repetition_count() >= 5 {
+            self.result = GameResult::Draw {
+                reason: DrawReason::Fivefold,
+            };
+        }
+    }
+
+    /// The draws that the side to move could claim right now. Claiming them
+    /// is up to the players, see [`Board::claim_draw`].
+    #[must_use]
+    pub fn claimable_draws(&self) -> Vec<DrawReason> {
+        let mut draws = Vec::new();
+        if self.result != GameResult::Undecided {
+            return draws;
+        }
+
+        if self.inner.halfmove_clock >= 100 {
+            draws.push(DrawReason::FiftyMove);
+        }
+
+        if self.is_threefold_repetition() {
+            draws.push(DrawReason::Threefold);
+        }
+
+        draws
+    }
+
+    /// Ends the game with a draw, if `reason` is one of the claimable draws or
+    /// an agreement between the players.
+    pub fn claim_draw(&mut self, reason: DrawReason) -> Result<(), ClaimDrawError> {
+        if self.result != GameResult::Undecided {
+            return Err(ClaimDrawError::GameOver);
+        }
+
+        if reason != DrawReason::Agreement && !self.claimable_draws().contains(&reason) {
+            return Err(ClaimDrawError::NotClaimable);
+        }
+
+        self.result = GameResult::Draw { reason };
+        Ok(())
+    }
+
+    /// How many times the current position has been on the board, counting
+    /// the current one.
+    #[must_use]
+    pub fn repetition_count(&self) -> usize {
+        let hash = self.inner.hash();
+        // Only positions with the same side to move can be repetitions.
+        self.history
+            .iter()
+            .rev()
+            .step_by(2)
+            .filter(|&&other| other == hash)
+            .count()
+    }
+
+    /// Whether the current position has been repeated three times, so that a
+    /// draw can be claimed. Fivefold repetitions end the game automatically.
+    #[must_use]
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 3
     }
 
     pub fn validate<'b, M>(&self, pseudomove: M) -> Option<LegalMove<'b>>
     where
-        M: TryInto<PseudoMove>,
+        M: TryIntoMove,
     {
-        let pseudomove = match pseudomove.try_into() {
+        let pseudomove = match pseudomove.try_into_move(&self.inner) {
             Ok(pm) => pm,
             Err(_) => return None,
         };
         LegalMove::new(pseudomove, &self.inner)
     }
 
-    pub fn moves(&self) -> MoveGenerator {
+    pub fn moves(&self) -> MoveGenerator<'_> {
         MoveGenerator::new(self)
     }
 
     pub fn perft(&self, depth: usize) -> usize {
-        fn perft(board: &BorkedBoard, depth: usize) -> usize {
-            if depth == 0 {
-                return 1;
-            }
-
-            let moves = board.moves();
-            let mut nodes = 0;
-            for movement in moves {
-                let mut clone = board.clone();
-                if clone.try_feed(movement.clone()).is_err() {
-                    continue;
-                }
-                nodes += perft(&clone, depth - 1);
-            }
-
-            nodes
-        }
+        self.inner.clone().perft(depth)
+    }
 
-        perft(&self.inner, depth)
+    #[must_use]
+    pub fn perft_stats(&self, depth: usize) -> PerftStats {
+        self.inner.clone().perft_stats(depth)
     }
 }
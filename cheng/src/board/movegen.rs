@@ -1,30 +1,68 @@
 use crate::{
-    movement::{Castle, MoveKind},
+    movegen::{self, Bishop, King, Knight, PieceExt, Rook},
+    movement::{self, Castle},
     pieces::Piece,
-    side_state::CastlingRights,
     square::Square,
-    Board, BorkedBoard, LegalMove, PseudoMove, Side, SidedPiece,
+    Board, BorkedBoard, LegalMove, MoveList, PackedMove, PseudoMove, Side, SidedPiece,
 };
 
-use super::BoardMask;
+use super::{
+    attacks::{between, line, pawn_attackers},
+    BoardMask,
+};
+
+/// Which moves a [`PseudoMoveGenerator`] produces. Searches can generate the
+/// moves in stages, e.g. captures before quiet moves, and skip the later
+/// stages after a cutoff.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GenerationMode {
+    #[default]
+    All,
+    /// Captures, en passant included, and every promotion.
+    Captures,
+    /// Moves that don't capture nor promote, castles included. Together with
+    /// [`GenerationMode::Captures`] they make up all the moves.
+    Quiets,
+    /// The moves that may get the king out of check: king moves, and captures
+    /// or blocks of a single checker. Without a check it's the same as
+    /// [`GenerationMode::All`].
+    Evasions,
+    /// The quiet moves that give check, directly or by discovery.
+    QuietChecks,
+}
 
 pub struct PseudoMoveGenerator<'a> {
     pub board: &'a BorkedBoard,
     pub side: Side,
-    pub cached_moves: Vec<PseudoMove>,
+    pub mode: GenerationMode,
+    pub cached_moves: MoveList,
     pub idx: usize,
 }
 
+/// Which squares each kind of piece may move to in the current mode.
+struct Targets {
+    pieces: BoardMask,
+    king: BoardMask,
+    /// Only for pawns, whose captures and pushes go to different squares.
+    pawns: BoardMask,
+    castles: bool,
+}
+
 impl<'a> PseudoMoveGenerator<'a> {
     pub fn new(board: &'a BorkedBoard) -> Self {
         Self::new_for_side(board, board.turn)
     }
 
     pub fn new_for_side(board: &'a BorkedBoard, side: Side) -> Self {
+        Self::with_mode(board, side, GenerationMode::All)
+    }
+
+    pub fn with_mode(board: &'a BorkedBoard, side: Side, mode: GenerationMode) -> Self {
         let mut gen = Self {
             board,
             side,
-            cached_moves: Vec::with_capacity(20),
+            mode,
+            cached_moves: MoveList::new(),
             idx: 0,
         };
 
@@ -41,97 +79,90 @@ impl<'a> PseudoMoveGenerator<'a> {
     }
 
     fn generate_all_moves(&mut self) {
-        self.generate_moves_ignoring_game_ended();
-        self.generate_castles_ignoring_game_ended();
-    }
-
-    #[inline]
-    fn add_move(&mut self, movement: PseudoMove) {
-        self.cached_moves.push(movement);
-    }
+        let mode = match self.mode {
+            GenerationMode::Evasions if !self.board.side(self.side).king_in_check => {
+                GenerationMode::All
+            }
+            mode => mode,
+        };
 
-    fn generate_castles_ignoring_game_ended(&mut self) {
-        use crate::prelude::*;
-        // The squares that must be unoccupied and unthreathened to be able
-        // to castle. For example, in white's king side castle, F1 and G1.
-        fn can_castle(
-            relevant_squares_occupancy: BoardMask,
-            relevant_squares_threats: BoardMask,
-            occupancy: BoardMask,
-            opposite_threats: BoardMask,
-        ) -> bool {
-            !relevant_squares_occupancy.has_coincidences(occupancy)
-                && !relevant_squares_threats.has_coincidences(opposite_threats)
+        match mode {
+            GenerationMode::QuietChecks => self.generate_quiet_checks(),
+            GenerationMode::Evasions => self.generate_evasions(),
+            _ => {
+                let targets = self.targets(mode);
+                self.generate_moves_ignoring_game_ended(&targets);
+                if targets.castles {
+                    self.generate_castles_ignoring_game_ended();
+                }
+            }
         }
+    }
 
-        let side = self.board.side(self.side);
-        let opposite_side = self.board.side(self.side.opposite());
-
-        if side.castling_rights == CastlingRights::None || side.king_in_check {
-            return;
-        }
+    fn targets(&self, mode: GenerationMode) -> Targets {
+        let friendly = self.board.side(self.side).occupancy;
+        let opposite = self.board.side(self.side.opposite()).occupancy;
+        let empty = friendly.with(opposite).opposite();
+        let promotion_rank = BoardMask::from(0xff00_0000_0000_00ff);
 
-        let king_square = Castle::king_square_before_castle(side.side);
-        let (queen_side_castle_square, king_side_castle_square) = match side.side {
-            Side::White => (C1, G1),
-            Side::Black => (C8, G8),
+        let en_passant = match self.board.side(self.side.opposite()).en_passant {
+            Some(square) => BoardMask::from(square),
+            None => BoardMask::default(),
         };
 
-        let occupancy = side.occupancy.with(opposite_side.occupancy);
-
-        if side.castling_rights.queen_side() {
-            let relevant_square_occupancy = Castle::QueenSide.relevant_square_occupancy(side.side);
-            let relevant_square_threats = Castle::QueenSide.relevant_square_threats(side.side);
-
-            if can_castle(
-                relevant_square_occupancy,
-                relevant_square_threats,
-                occupancy,
-                opposite_side.threats,
-            ) {
-                let queen_side_castle = PseudoMove {
-                    origin: king_square,
-                    destination: queen_side_castle_square,
-                    kind: MoveKind::Castle(Castle::QueenSide),
-                };
-
-                self.add_move(queen_side_castle);
+        match mode {
+            GenerationMode::Captures => Targets {
+                pieces: opposite,
+                king: opposite,
+                pawns: opposite.with(en_passant).with(promotion_rank),
+                castles: false,
+            },
+            GenerationMode::Quiets | GenerationMode::QuietChecks => Targets {
+                pieces: empty,
+                king: empty,
+                pawns: empty.without(en_passant).without(promotion_rank),
+                castles: true,
+            },
+            GenerationMode::All | GenerationMode::Evasions => {
+                let all = BoardMask::from(u64::MAX);
+                Targets {
+                    pieces: all,
+                    king: all,
+                    pawns: all,
+                    castles: true,
+                }
             }
         }
+    }
 
-        if side.castling_rights.king_side() {
-            let relevant_squares_occupancy = Castle::KingSide.relevant_square_occupancy(side.side);
-            let relevant_squares_threats = Castle::KingSide.relevant_square_threats(side.side);
-
-            if can_castle(
-                relevant_squares_occupancy,
-                relevant_squares_threats,
-                occupancy,
-                opposite_side.threats,
-            ) {
-                let king_side_castle = PseudoMove {
-                    origin: king_square,
-                    destination: king_side_castle_square,
-                    kind: MoveKind::Castle(Castle::KingSide),
-                };
+    #[inline]
+    fn add_move(&mut self, movement: PackedMove) {
+        self.cached_moves.push(movement);
+    }
 
-                self.add_move(king_side_castle);
+    fn generate_castles_ignoring_game_ended(&mut self) {
+        for castle in [Castle::QueenSide, Castle::KingSide] {
+            if self.board.can_castle(self.side, castle) {
+                let movement = self.board.castle_move(self.side, castle);
+                self.add_move(PackedMove::castle(
+                    movement.origin,
+                    movement.destination,
+                    castle,
+                ));
             }
         }
     }
 
-    fn generate_moves_ignoring_game_ended(&mut self) {
+    fn generate_moves_ignoring_game_ended(&mut self, targets: &Targets) {
         let friendly = self.board.side(self.side).occupancy;
         let opposite = self.board.side(self.side.opposite()).occupancy;
         let opposite_threats = self.board.side(self.side.opposite()).threats;
 
-        for piece in Piece::iter() {
-            for piece_square in self.board.side(self.side).pieces.piece(piece) {
-                if piece == Piece::Pawn {
-                    self.generate_pawn_moves(piece_square);
-                    continue;
-                }
+        let pawns = self.board.side(self.side).pieces.piece(Piece::Pawn);
+        self.generate_pawn_moves(pawns, targets.pawns);
 
+        for piece in Piece::iter().filter(|&piece| piece != Piece::Pawn) {
+            for piece_square in self.board.side(self.side).pieces.piece(piece) {
                 let moves = crate::movegen::moves(
                     SidedPiece(self.side, piece),
                     piece_square,
@@ -140,66 +171,250 @@ impl<'a> PseudoMoveGenerator<'a> {
                 );
 
                 let moves = if piece == Piece::King {
-                    moves.without(opposite_threats)
+                    moves.without(opposite_threats).only(targets.king)
                 } else {
-                    moves
+                    moves.only(targets.pieces)
                 };
 
-                for destination in moves {
-                    let movement = PseudoMove {
-                        origin: piece_square,
-                        destination,
-                        kind: MoveKind::Move,
-                    };
+                self.add_piece_moves(piece_square, moves, opposite);
+            }
+        }
+    }
+
+    fn add_piece_moves(&mut self, origin: Square, destinations: BoardMask, opposite: BoardMask) {
+        for destination in destinations {
+            let movement = if opposite.get(destination) {
+                PackedMove::capture(origin, destination)
+            } else {
+                PackedMove::quiet(origin, destination)
+            };
+
+            self.add_move(movement);
+        }
+    }
+
+    fn generate_pawn_moves(&mut self, pawns: BoardMask, targets: BoardMask) {
+        push_pawn_moves(
+            self.board,
+            self.side,
+            pawns,
+            targets,
+            true,
+            &mut self.cached_moves,
+        );
+    }
+
+    fn generate_evasions(&mut self) {
+        let state = self.board.side(self.side);
+        let opposite = self.board.side(self.side.opposite());
+        let Some(king) = state.king_square() else {
+            return;
+        };
 
-                    self.add_move(movement);
+        let checkers = self.board.attackers_to(king, self.side.opposite());
+
+        let king_moves =
+            King::moves(king, state.occupancy, opposite.occupancy).without(opposite.threats);
+        self.add_piece_moves(king, king_moves, opposite.occupancy);
+
+        // Only the king can get out of a double check.
+        let Some(checker) = checkers.first() else {
+            return;
+        };
+        if checkers.count() > 1 {
+            return;
+        }
+
+        let blocks = between(king, checker).with(checkers);
+        let pawn_targets = match opposite.en_passant {
+            // The checker is the pawn that can be taken en passant.
+            Some(square) if square.next_rank(opposite.side) == checker => {
+                blocks.with(BoardMask::from(square))
+            }
+            _ => blocks,
+        };
+
+        self.generate_pawn_moves(state.pieces.piece(Piece::Pawn), pawn_targets);
+
+        for piece in Piece::iter() {
+            for square in state.pieces.piece(piece) {
+                match piece {
+                    Piece::King | Piece::Pawn => {}
+                    _ => {
+                        let moves = crate::movegen::moves(
+                            SidedPiece(self.side, piece),
+                            square,
+                            state.occupancy,
+                            opposite.occupancy,
+                        )
+                        .only(blocks);
+                        self.add_piece_moves(square, moves, opposite.occupancy);
+                    }
                 }
             }
         }
     }
 
-    fn generate_pawn_moves(&mut self, square: Square) {
-        use crate::prelude::{A2, A7};
-
-        let friendly = self.board.side(self.side).occupancy;
-        let opposite = self.board.side(self.side.opposite()).occupancy;
+    fn generate_quiet_checks(&mut self) {
+        let state = self.board.side(self.side);
+        let opposite = self.board.side(self.side.opposite());
+        let Some(their_king) = opposite.king_square() else {
+            return;
+        };
 
-        let opposite = match self.board.side(self.side.opposite()).en_passant {
-            Some(square) => opposite.with(BoardMask::from(square)),
-            None => opposite,
+        let occupancy = state.occupancy.with(opposite.occupancy);
+        let targets = self.targets(GenerationMode::QuietChecks);
+        let discoverers = self.board.discovered_check_candidates(self.side);
+
+        let empty = BoardMask::default();
+        let side = self.side;
+        let check_squares = |piece| match piece {
+            Piece::Pawn => pawn_attackers(side, their_king),
+            Piece::Knight => Knight::threats(their_king, empty, empty),
+            Piece::Bishop => Bishop::threats(their_king, occupancy, empty),
+            Piece::Rook => Rook::threats(their_king, occupancy, empty),
+            Piece::Queen => Bishop::threats(their_king, occupancy, empty)
+                .with(Rook::threats(their_king, occupancy, empty)),
+            Piece::King => empty,
         };
 
-        let moves = crate::movegen::moves(
-            SidedPiece(self.side, Piece::Pawn),
-            square,
-            friendly,
-            opposite,
+        // Pawns that may give check by discovery have their own targets.
+        let pawns = state.pieces.piece(Piece::Pawn);
+        self.generate_pawn_moves(
+            pawns.without(discoverers),
+            check_squares(Piece::Pawn).only(targets.pawns),
         );
 
-        let moves_are_promotion = match self.side {
-            Side::White => A7.rank::<usize>() == square.rank(),
-            Side::Black => A2.rank::<usize>() == square.rank(),
-        };
+        for piece in Piece::iter() {
+            let squares = match piece {
+                Piece::Pawn => pawns.only(discoverers),
+                _ => state.pieces.piece(piece),
+            };
+
+            for square in squares {
+                let mut checks = check_squares(piece);
+                if discoverers.get(square) {
+                    // Moving off the line between the king and the slider
+                    // behind uncovers it.
+                    checks = checks.with(line(their_king, square).opposite());
+                }
 
-        if moves_are_promotion {
-            for destination in moves {
-                for piece in Piece::iter_promotable_pieces() {
-                    let movement = PseudoMove {
-                        origin: square,
-                        destination,
-                        kind: MoveKind::Promote(piece),
-                    };
-                    self.add_move(movement);
+                if piece == Piece::Pawn {
+                    self.generate_pawn_moves(BoardMask::from(square), checks.only(targets.pawns));
+                    continue;
+                }
+
+                let mut moves = crate::movegen::moves(
+                    SidedPiece(self.side, piece),
+                    square,
+                    state.occupancy,
+                    opposite.occupancy,
+                );
+                if piece == Piece::King {
+                    moves = moves.without(opposite.threats);
                 }
+                self.add_piece_moves(
+                    square,
+                    moves.only(checks).only(targets.pieces),
+                    opposite.occupancy,
+                );
+            }
+        }
+
+        for castle in [Castle::QueenSide, Castle::KingSide] {
+            if self.board.can_castle(self.side, castle) && self.castle_gives_check(castle) {
+                let movement = self.board.castle_move(self.side, castle);
+                self.add_move(PackedMove::castle(
+                    movement.origin,
+                    movement.destination,
+                    castle,
+                ));
+            }
+        }
+    }
+
+    fn castle_gives_check(&self, castle: Castle) -> bool {
+        let state = self.board.side(self.side);
+        let opposite = self.board.side(self.side.opposite());
+        let (Some(king), Some(their_king)) = (state.king_square(), opposite.king_square()) else {
+            return false;
+        };
+
+        let rook_after = castle.rook_square_after_castle(self.side);
+        let occupancy = state
+            .occupancy
+            .with(opposite.occupancy)
+            .without(BoardMask::from(king))
+            .without(BoardMask::from(state.castling_rook_square(castle)))
+            .with(BoardMask::from(castle.king_square_after_castle(self.side)))
+            .with(BoardMask::from(rook_after));
+
+        let empty = BoardMask::default();
+        Rook::threats(rook_after, occupancy, empty).get(their_king)
+            || self
+                .board
+                .attackers_to_with_occupancy(their_king, self.side, occupancy)
+                != empty
+    }
+}
+
+/// Pushes the moves of all the pawns of `side` in `pawns` that land on
+/// `targets`, generated at once by shifting the whole set. En passant is only
+/// included if `en_passant` is set.
+pub(super) fn push_pawn_moves(
+    board: &BorkedBoard,
+    side: Side,
+    pawns: BoardMask,
+    targets: BoardMask,
+    en_passant: bool,
+    moves: &mut MoveList,
+) {
+    let ours = board.side(side);
+    let theirs = board.side(side.opposite());
+    let empty = ours.occupancy.with(theirs.occupancy).opposite();
+    let promotion_rank = BoardMask::from(0xff00_0000_0000_00ff);
+
+    let en_passant = match theirs.en_passant {
+        Some(square) if en_passant => BoardMask::from(square),
+        _ => BoardMask::default(),
+    };
+
+    let origin = |destination: Square, offset: i32| {
+        Square::from_index((destination.to_index() as i32 - offset) as usize)
+    };
+
+    let push = movegen::pawn_push_offset(side);
+    for destination in movegen::pawn_pushes(side, pawns, empty).only(targets) {
+        let origin = origin(destination, push);
+        if promotion_rank.get(destination) {
+            for piece in Piece::iter_promotable_pieces() {
+                moves.push(PackedMove::promotion(origin, destination, piece, false));
             }
         } else {
-            for destination in moves {
-                let movement = PseudoMove {
-                    origin: square,
-                    destination,
-                    kind: MoveKind::Move,
-                };
-                self.add_move(movement);
+            moves.push(PackedMove::quiet(origin, destination));
+        }
+    }
+
+    for destination in movegen::pawn_double_pushes(side, pawns, empty).only(targets) {
+        moves.push(PackedMove::double_pawn_push(
+            origin(destination, 2 * push),
+            destination,
+        ));
+    }
+
+    let victims = theirs.occupancy.with(en_passant).only(targets);
+    for west in [true, false] {
+        let offset = movegen::pawn_capture_offset(side, west);
+        for destination in movegen::pawn_attacks_towards(side, pawns, west).only(victims) {
+            let origin = origin(destination, offset);
+            if promotion_rank.get(destination) {
+                for piece in Piece::iter_promotable_pieces() {
+                    moves.push(PackedMove::promotion(origin, destination, piece, true));
+                }
+            } else if en_passant.get(destination) {
+                moves.push(PackedMove::en_passant(origin, destination));
+            } else {
+                moves.push(PackedMove::capture(origin, destination));
             }
         }
     }
@@ -209,20 +424,24 @@ impl<'a> Iterator for PseudoMoveGenerator<'a> {
     type Item = PseudoMove;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let pseudomove = self.cached_moves.get(self.idx)?;
+        let movement = *self.cached_moves.get(self.idx)?;
         self.idx += 1;
-        Some(pseudomove.clone())
+        Some(movement.into())
     }
 }
 
+/// Iterates over the legal moves of a position, see
+/// [`BorkedBoard::legal_moves`].
 pub struct MoveGenerator<'a> {
-    pub inner: PseudoMoveGenerator<'a>,
+    board: &'a BorkedBoard,
+    moves: movement::list::IntoIter,
 }
 
 impl<'a> MoveGenerator<'a> {
     pub fn new(board: &'a Board) -> Self {
         Self {
-            inner: PseudoMoveGenerator::new(board.inner()),
+            board: board.inner(),
+            moves: board.inner().legal_moves().into_iter(),
         }
     }
 }
@@ -231,12 +450,7 @@ impl<'a> Iterator for MoveGenerator<'a> {
     type Item = LegalMove<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut pseudomove;
-        loop {
-            pseudomove = self.inner.next()?;
-            if !self.inner.board.does_move_bork(pseudomove.clone()) {
-                return Some(unsafe { LegalMove::unchecked_new(pseudomove, self.inner.board) });
-            }
-        }
+        let movement = self.moves.next()?;
+        Some(unsafe { LegalMove::unchecked_new(movement.into(), self.board) })
     }
 }
@@ -3,8 +3,28 @@ use crate::{
     Board, BorkedBoard, Castle, FromIntoFen, Piece, Side, Square,
 };
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum FENParsingError {
+/// Where a FEN failed to parse, and why.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FENParsingError {
+    pub field: FenField,
+    /// Characters from the start of the FEN.
+    pub offset: usize,
+    pub kind: FENParsingErrorKind,
+}
+
+/// The six fields of a FEN, in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FenField {
+    Placement,
+    Turn,
+    Castling,
+    EnPassant,
+    HalfMoveClock,
+    FullMoveClock,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FENParsingErrorKind {
     MissingPart,
     TooManyParts,
     SquareUnderflow,
@@ -16,25 +36,180 @@ pub enum FENParsingError {
     WrongEnPassantSquare,
     InvalidHalfMoveClock,
     InvalidFullMoveClock,
+    // Only with `FenValidation::Strict`, or when parsing a `Board`.
+    PawnOnBackRank,
+    MissingKing(Side),
+    TooManyKings(Side),
+    /// The side that just moved left its king in check.
+    SideNotToMoveInCheck,
+    /// No pawn could have just made a two square move past the square.
+    ImpossibleEnPassant,
+    /// Castling rights without the king or the rook where they should be.
+    ImpossibleCastleRights,
+}
+
+/// How much [`BorkedBoard::from_fen_with`] checks the position besides the
+/// syntax of the FEN.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FenValidation {
+    /// Rejects positions that can't be reached in a game: pawns on the first
+    /// or last ranks, a side without exactly one king, the side not to move in
+    /// check, en passant squares without the pawn that just moved, and
+    /// castling rights without their king and rook.
+    #[default]
+    Strict,
+    /// Only rejects malformed FENs. Castling rights without their king and
+    /// rook are ignored.
+    Lenient,
+}
+
+/// How [`BorkedBoard::as_fen_with`] writes a FEN.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FenWriteOptions {
+    pub en_passant: FenEnPassant,
+    pub castling: FenCastling,
+    /// Without them the FEN has only the four fields of an EPD.
+    pub move_counters: bool,
+}
+
+impl Default for FenWriteOptions {
+    /// The FEN most tools write: the same position always gives the same FEN.
+    fn default() -> Self {
+        Self {
+            en_passant: FenEnPassant::Legal,
+            castling: FenCastling::XFen,
+            move_counters: true,
+        }
+    }
+}
+
+/// When the en passant square is written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FenEnPassant {
+    /// After every two square pawn move, as this crate used to.
+    Always,
+    /// When a pawn of the side to move attacks the square, as lichess does.
+    Capturable,
+    /// When an en passant capture is legal.
+    #[default]
+    Legal,
+}
+
+/// How the castling rights are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FenCastling {
+    /// The usual `KQkq`, or the file of the rook when another rook is between
+    /// it and the corner.
+    #[default]
+    XFen,
+    /// The files of the castling rooks.
+    Shredder,
 }
 
 impl FromIntoFen for Board {
     type Error = FENParsingError;
 
+    /// Writes the FEN with the default [`FenWriteOptions`].
     fn as_fen(&self) -> String {
         self.inner.as_fen()
     }
 
+    /// Parses the FEN with [`FenValidation::Strict`].
     fn from_fen(fen: &str) -> Result<Self, Self::Error> {
-        Ok(Board::try_from(BorkedBoard::from_fen(fen)?).unwrap())
+        Self::from_fen_with(fen, FenValidation::Strict)
     }
 }
 
-impl FromIntoFen for BorkedBoard {
-    type Error = FENParsingError;
+impl Board {
+    /// Parses `fen`, checking as much as `validation` says. Even with
+    /// [`FenValidation::Lenient`] the side not to move can't be in check.
+    pub fn from_fen_with(fen: &str, validation: FenValidation) -> Result<Self, FENParsingError> {
+        let board = BorkedBoard::from_fen_with(fen, validation)?;
+        let turn = field_offset(fen, FenField::Turn);
+
+        Board::try_from(board).map_err(|()| FENParsingError {
+            field: FenField::Turn,
+            offset: turn,
+            kind: FENParsingErrorKind::SideNotToMoveInCheck,
+        })
+    }
 
+    /// Like [`FromIntoFen::as_fen`], but writing the castling rights as the
+    /// files of the castling rooks.
     #[must_use]
-    fn as_fen(&self) -> String {
+    pub fn as_shredder_fen(&self) -> String {
+        self.inner.as_shredder_fen()
+    }
+
+    #[must_use]
+    pub fn as_fen_with(&self, options: &FenWriteOptions) -> String {
+        self.inner.as_fen_with(options)
+    }
+}
+
+impl BorkedBoard {
+    /// Like [`FromIntoFen::as_fen`], but writing the castling rights as the
+    /// files of the castling rooks.
+    #[must_use]
+    pub fn as_shredder_fen(&self) -> String {
+        self.as_fen_with(&FenWriteOptions {
+            castling: FenCastling::Shredder,
+            ..FenWriteOptions::default()
+        })
+    }
+
+    /// The en passant square, if `en_passant` says to write it.
+    fn en_passant_fen(&self, en_passant: FenEnPassant) -> Option<Square> {
+        let square = self.side(self.turn.opposite()).en_passant?;
+
+        let written = match en_passant {
+            FenEnPassant::Always => true,
+            FenEnPassant::Capturable => self.capturable_en_passant().is_some(),
+            FenEnPassant::Legal => self
+                .legal_moves()
+                .iter()
+                .any(|movement| movement.is_en_passant()),
+        };
+        written.then_some(square)
+    }
+
+    /// Castling rights as X-FEN, which is the usual `KQkq` unless there is
+    /// another rook between the castling rook and the corner, or as
+    /// Shredder-FEN.
+    fn castling_rights_fen(&self, castling: FenCastling) -> String {
+        let mut fen = String::new();
+        for state in [&self.white_side, &self.black_side] {
+            for castle in [Castle::KingSide, Castle::QueenSide] {
+                if !state.castling_rights.contains(castle) {
+                    continue;
+                }
+
+                let rook = state.castling_rook_square(castle);
+                let xfen = castling == FenCastling::XFen;
+                let chr = if xfen && outermost_rook(state, castle) == Some(rook) {
+                    match castle {
+                        Castle::KingSide => 'k',
+                        Castle::QueenSide => 'q',
+                    }
+                } else {
+                    char::from(b'a' + rook.file::<u8>())
+                };
+
+                fen.push(match state.side {
+                    Side::White => chr.to_ascii_uppercase(),
+                    Side::Black => chr,
+                });
+            }
+        }
+
+        if fen.is_empty() {
+            fen.push('-');
+        }
+        fen
+    }
+
+    #[must_use]
+    pub fn as_fen_with(&self, options: &FenWriteOptions) -> String {
         use std::fmt::Write;
 
         let mut fen = String::new();
@@ -47,7 +222,7 @@ impl FromIntoFen for BorkedBoard {
                     Some(sided_piece) => fen.push(char::from(*sided_piece)),
                     None => {
                         let mut accum = 1;
-                        while iterator.peek().map_or(false, |piece| piece.is_none()) {
+                        while iterator.peek().is_some_and(|piece| piece.is_none()) {
                             accum += 1;
                             iterator.next();
                         }
@@ -61,58 +236,111 @@ impl FromIntoFen for BorkedBoard {
             }
         }
 
-        // We always include the en passant square, if it exists. Note that lichess
-        // only includes it if the capture is possible (i.e. there is a pawn to make
-        // an en passant capture). This is simpler for now.
         let en_passant_str = self
-            .side(self.turn.opposite())
-            .en_passant
+            .en_passant_fen(options.en_passant)
             .map_or("-".to_string(), |sq| format!("{sq:?}"));
 
-        let castling_rights = {
-            let white_castling_rights = self
-                .white_side
-                .castling_rights
-                .to_fen_str()
-                .to_ascii_uppercase();
-            let black_castling_rights = self.black_side.castling_rights.to_fen_str();
-
-            if white_castling_rights.is_empty() && black_castling_rights.is_empty() {
-                String::from("-")
-            } else {
-                format!("{white_castling_rights}{black_castling_rights}")
-            }
-        };
-
-        let hmove = self.halfmove_clock;
-        let fmove = self.fullmove_clock;
+        let castling_rights = self.castling_rights_fen(options.castling);
 
         write!(fen, " {}", char::from(self.turn)).unwrap();
-        write!(fen, " {castling_rights} {en_passant_str} {hmove} {fmove}").unwrap();
+        write!(fen, " {castling_rights} {en_passant_str}").unwrap();
+
+        if options.move_counters {
+            let hmove = self.halfmove_clock;
+            let fmove = self.fullmove_clock;
+            write!(fen, " {hmove} {fmove}").unwrap();
+        }
 
         fen
     }
+}
+
+impl FromIntoFen for BorkedBoard {
+    type Error = FENParsingError;
+
+    /// Writes the FEN with the default [`FenWriteOptions`].
+    fn as_fen(&self) -> String {
+        self.as_fen_with(&FenWriteOptions::default())
+    }
 
+    /// Parses the FEN with [`FenValidation::Lenient`], as any position can be
+    /// set up on a `BorkedBoard`.
     fn from_fen(fen: &str) -> Result<Self, FENParsingError> {
-        use FENParsingError::*;
+        Self::from_fen_with(fen, FenValidation::Lenient)
+    }
+}
 
-        let fen = fen.trim();
-        let mut parts = fen.split(' ');
-        let board = parts.next().ok_or(MissingPart)?;
+/// The character offset in `fen` of the start of `field`, or of its end if the
+/// field is missing.
+fn field_offset(fen: &str, field: FenField) -> usize {
+    let trimmed = fen.trim_start();
+    let leading = fen.len() - trimmed.len();
+
+    let mut start = leading;
+    for (i, part) in trimmed.trim_end().split(' ').enumerate() {
+        if i == field as usize {
+            return fen[..start].chars().count();
+        }
+        start += part.len() + 1;
+    }
+
+    fen.chars().count()
+}
+
+impl BorkedBoard {
+    /// Parses `fen`, checking as much as `validation` says.
+    pub fn from_fen_with(fen: &str, validation: FenValidation) -> Result<Self, FENParsingError> {
+        use FENParsingErrorKind::*;
+
+        let strict = validation == FenValidation::Strict;
+        let error = |field, offset, kind| FENParsingError {
+            field,
+            offset,
+            kind,
+        };
+        let at = |field, kind| error(field, field_offset(fen, field), kind);
+
+        let mut parts = fen.trim().split(' ');
+        let board = parts
+            .next()
+            .ok_or_else(|| at(FenField::Placement, MissingPart))?;
 
         let mut white_side = SideState::empty(Side::White);
         let mut black_side = SideState::empty(Side::Black);
 
+        let placement = field_offset(fen, FenField::Placement);
         let mut squares = Square::iter_all();
 
-        for rank in board.split('/').rev() {
-            for piece_char in rank.chars() {
-                if let Some(digit) = piece_char.to_digit(10) {
-                    squares.nth(digit as usize - 1).ok_or(SquareOverflow)?;
+        // The ranks are written from the eighth to the first, but the squares
+        // are iterated from the first.
+        let ranks: Vec<_> = board
+            .split('/')
+            .scan(placement, |start, rank| {
+                let rank_start = *start;
+                *start += rank.chars().count() + 1;
+                Some((rank_start, rank))
+            })
+            .collect();
+        if let Some(&(ninth_rank, _)) = ranks.get(8) {
+            return Err(error(FenField::Placement, ninth_rank, SquareOverflow));
+        }
+
+        for &(rank_start, rank) in ranks.iter().rev() {
+            for (i, piece_char) in rank.chars().enumerate() {
+                let offset = rank_start + i;
+                if let Some(digit @ 1..=8) = piece_char.to_digit(10) {
+                    squares.nth(digit as usize - 1).ok_or(error(
+                        FenField::Placement,
+                        offset,
+                        SquareOverflow,
+                    ))?;
                     continue;
                 }
 
-                let square = squares.next().ok_or(SquareOverflow)?;
+                let square =
+                    squares
+                        .next()
+                        .ok_or(error(FenField::Placement, offset, SquareOverflow))?;
 
                 let side = if piece_char.is_ascii_uppercase() {
                     &mut white_side
@@ -120,104 +348,240 @@ impl FromIntoFen for BorkedBoard {
                     &mut black_side
                 };
 
-                let piece: Piece = piece_char
-                    .to_ascii_lowercase()
-                    .try_into()
-                    .or(Err(UnknownPiece))?;
+                let piece: Piece = piece_char.to_ascii_lowercase().try_into().or(Err(error(
+                    FenField::Placement,
+                    offset,
+                    UnknownPiece,
+                )))?;
+
+                if strict {
+                    let back_rank = [0, 7].contains(&square.rank::<usize>());
+                    if piece == Piece::Pawn && back_rank {
+                        return Err(error(FenField::Placement, offset, PawnOnBackRank));
+                    }
+                    if piece == Piece::King && side.king_square().is_some() {
+                        return Err(error(FenField::Placement, offset, TooManyKings(side.side)));
+                    }
+                }
+
                 side.put(square, piece);
             }
 
+            let offset = rank_start + rank.chars().count();
             if squares
                 .next_non_consuming()
-                .map_or(false, |sq| sq.file::<usize>() != 0)
+                .is_some_and(|sq| sq.file::<usize>() != 0)
             {
-                return Err(InvalidAlignment);
+                return Err(error(FenField::Placement, offset, InvalidAlignment));
             }
         }
 
         if squares.next().is_some() {
-            return Err(SquareUnderflow);
+            let placement_end = placement + board.chars().count();
+            return Err(error(FenField::Placement, placement_end, SquareUnderflow));
+        }
+
+        if strict {
+            for side in [&white_side, &black_side] {
+                if side.king_square().is_none() {
+                    return Err(error(
+                        FenField::Placement,
+                        placement,
+                        MissingKing(side.side),
+                    ));
+                }
+            }
         }
 
         let turn = match parts.next() {
             Some("w") => Side::White,
             Some("b") => Side::Black,
-            Some(_) => return Err(InvalidTurn),
-            None => return Err(MissingPart),
+            Some(_) => return Err(at(FenField::Turn, InvalidTurn)),
+            None => return Err(at(FenField::Turn, MissingPart)),
         };
 
-        let (white_castle_rights, black_castle_rights) = {
-            let castle_rights_str = parts.next().ok_or(MissingPart)?;
-            match CastlingRights::parse_fen_from_str(castle_rights_str) {
-                Some(castle_rights) => castle_rights,
-                None => return Err(InvalidCastleRights),
-            }
-        };
-
-        if are_castling_rights_ok(white_castle_rights, &white_side) {
-            white_side.castling_rights = white_castle_rights;
-        }
-
-        if are_castling_rights_ok(black_castle_rights, &black_side) {
-            black_side.castling_rights = black_castle_rights;
-        }
-
-        let en_passant_square = parts.next().ok_or(MissingPart)?;
+        let castle_rights_str = parts
+            .next()
+            .ok_or_else(|| at(FenField::Castling, MissingPart))?;
+        let shredder =
+            parse_castling_rights(castle_rights_str, &mut white_side, &mut black_side, strict)
+                .map_err(|(i, kind)| {
+                    error(
+                        FenField::Castling,
+                        field_offset(fen, FenField::Castling) + i,
+                        kind,
+                    )
+                })?;
+
+        let en_passant_square = parts
+            .next()
+            .ok_or_else(|| at(FenField::EnPassant, MissingPart))?;
         if en_passant_square != "-" {
             let Ok(en_passant_square) = en_passant_square.parse() else {
-                return Err(WrongEnPassantSquare);
+                return Err(at(FenField::EnPassant, WrongEnPassantSquare));
             };
 
-            if turn == Side::White {
-                black_side.en_passant = Some(en_passant_square);
-            } else {
-                white_side.en_passant = Some(en_passant_square);
+            let (us, them) = match turn {
+                Side::White => (&white_side, &mut black_side),
+                Side::Black => (&black_side, &mut white_side),
+            };
+            if strict && !en_passant_is_possible(en_passant_square, us, them) {
+                return Err(at(FenField::EnPassant, ImpossibleEnPassant));
             }
+
+            them.set_en_passant(Some(en_passant_square));
         }
 
         let halfmove_clock = parts
             .next()
-            .ok_or(MissingPart)?
+            .ok_or_else(|| at(FenField::HalfMoveClock, MissingPart))?
             .parse()
-            .map_err(|_| InvalidHalfMoveClock)?;
+            .map_err(|_| at(FenField::HalfMoveClock, InvalidHalfMoveClock))?;
         let fullmove_clock = parts
             .next()
-            .ok_or(MissingPart)?
+            .ok_or_else(|| at(FenField::FullMoveClock, MissingPart))?
             .parse()
-            .map_err(|_| InvalidFullMoveClock)?;
+            .map_err(|_| at(FenField::FullMoveClock, InvalidFullMoveClock))?;
 
         if parts.next().is_some() {
-            Err(TooManyParts)
-        } else {
-            Ok(Self {
-                white_side,
-                black_side,
-                turn,
-                halfmove_clock,
-                fullmove_clock,
-            })
+            // Points right after the fullmove clock.
+            let fullmove_end = field_offset(fen, FenField::FullMoveClock)
+                + fen
+                    .trim()
+                    .split(' ')
+                    .nth(5)
+                    .map_or(0, |part| part.chars().count());
+            return Err(error(FenField::FullMoveClock, fullmove_end, TooManyParts));
+        }
+
+        let mut board = Self {
+            white_side,
+            black_side,
+            turn,
+            halfmove_clock,
+            fullmove_clock,
+            chess960: false,
+        };
+        board.chess960 = shredder || board.needs_chess960();
+
+        board.white_side.update_threats(&board.black_side);
+        board.black_side.update_threats(&board.white_side);
+        board.white_side.update_king_in_check(&board.black_side);
+        board.black_side.update_king_in_check(&board.white_side);
+
+        if strict && board.is_borked() {
+            return Err(at(FenField::Turn, SideNotToMoveInCheck));
         }
+
+        Ok(board)
     }
 }
 
-#[inline(always)]
-fn are_castling_rights_ok(rights: CastlingRights, side: &SideState) -> bool {
-    let queenside_ok = || -> bool {
-        side.pieces
-            .piece(Piece::Rook)
-            .get(Castle::QueenSide.rook_square_before_castle(side.side))
+/// Whether a pawn of `them` can have just moved two squares past `square`,
+/// with `us` to move.
+fn en_passant_is_possible(square: Square, us: &SideState, them: &SideState) -> bool {
+    let rank = match us.side {
+        Side::White => 5,
+        Side::Black => 2,
     };
+    if square.rank::<usize>() != rank {
+        return false;
+    }
 
-    let kingside_ok = || -> bool {
-        side.pieces
-            .piece(Piece::Rook)
-            .get(Castle::KingSide.rook_square_before_castle(side.side))
-    };
+    let pawn = square.next_rank(them.side);
+    let origin = square.next_rank(us.side);
+    let occupancy = us.occupancy.with(them.occupancy);
+
+    them.pieces.piece(Piece::Pawn).get(pawn) && !occupancy.get(square) && !occupancy.get(origin)
+}
+
+/// The rook furthest from the king on the side of `castle`, which is the one
+/// `K` and `Q` refer to in X-FEN.
+fn outermost_rook(side: &SideState, castle: Castle) -> Option<Square> {
+    let king = side.king_square()?;
+    let rooks = side
+        .pieces
+        .piece(Piece::Rook)
+        .into_iter()
+        .filter(|rook| rook.rank::<usize>() == king.rank::<usize>());
+
+    match castle {
+        Castle::KingSide => rooks
+            .filter(|rook| rook.file::<usize>() > king.file())
+            .max_by_key(|rook| rook.file::<usize>()),
+        Castle::QueenSide => rooks
+            .filter(|rook| rook.file::<usize>() < king.file())
+            .min_by_key(|rook| rook.file::<usize>()),
+    }
+}
 
-    match rights {
-        CastlingRights::None => true,
-        CastlingRights::QueenSide => queenside_ok(),
-        CastlingRights::KingSide => kingside_ok(),
-        CastlingRights::Both => queenside_ok() && kingside_ok(),
+/// Parses the castling rights of both sides, as `KQkq` (X-FEN, where they
+/// refer to the outermost rooks) or as the files of the castling rooks
+/// (Shredder-FEN). Rights without a king and a rook on the back rank are
+/// ignored, or an error if `strict`. Returns whether files were used, or the
+/// offending character and the error.
+fn parse_castling_rights(
+    castling_rights: &str,
+    white_side: &mut SideState,
+    black_side: &mut SideState,
+    strict: bool,
+) -> Result<bool, (usize, FENParsingErrorKind)> {
+    use crate::prelude::{A1, A8};
+
+    if castling_rights == "-" {
+        return Ok(false);
     }
+
+    let mut shredder = false;
+    for (i, chr) in castling_rights.chars().enumerate() {
+        let side = if chr.is_ascii_uppercase() {
+            &mut *white_side
+        } else {
+            &mut *black_side
+        };
+
+        let back_rank = match side.side {
+            Side::White => A1.rank::<usize>(),
+            Side::Black => A8.rank::<usize>(),
+        };
+        let king = side
+            .king_square()
+            .filter(|king| king.rank::<usize>() == back_rank);
+
+        let rook = match chr.to_ascii_lowercase() {
+            'k' => outermost_rook(side, Castle::KingSide),
+            'q' => outermost_rook(side, Castle::QueenSide),
+            file @ 'a'..='h' => {
+                shredder = true;
+                let rook = Square::from_rank_file(back_rank, file as usize - 'a' as usize);
+                side.pieces.piece(Piece::Rook).get(rook).then_some(rook)
+            }
+            _ => return Err((i, FENParsingErrorKind::InvalidCastleRights)),
+        };
+
+        let (Some(king), Some(rook)) = (king, rook) else {
+            if strict {
+                return Err((i, FENParsingErrorKind::ImpossibleCastleRights));
+            }
+            continue;
+        };
+        if rook.rank::<usize>() != back_rank {
+            continue;
+        }
+
+        let castle = if rook.file::<usize>() > king.file() {
+            Castle::KingSide
+        } else {
+            Castle::QueenSide
+        };
+
+        let mut rights = side.castling_rights;
+        rights
+            .checked_add(CastlingRights::from(castle))
+            .map_err(|_| (i, FENParsingErrorKind::InvalidCastleRights))?;
+        side.set_castling_rights(rights);
+        side.castling_rooks[castle as usize] = rook;
+    }
+
+    Ok(shredder)
 }
@@ -0,0 +1,113 @@
+use crate::{movement::MoveKind, BorkedBoard, Piece, PseudoMove, Side, Square};
+
+use super::BoardMask;
+
+/// Values used by the static exchange evaluation, in centipawns. The king is
+/// never captured, so its value doesn't matter.
+const fn see_value(piece: Piece) -> i32 {
+    match piece {
+        Piece::Pawn => 100,
+        Piece::Knight | Piece::Bishop => 300,
+        Piece::Rook => 500,
+        Piece::Queen => 900,
+        Piece::King => 0,
+    }
+}
+
+impl BorkedBoard {
+    /// Static exchange evaluation: the material the side to move wins (or
+    /// loses, if negative) with `movement` if both sides keep capturing on its
+    /// destination with their least valuable piece for as long as it pays off.
+    ///
+    /// Sliders behind other attackers join the exchange as the pieces in
+    /// front of them capture. Promotions are only accounted for on
+    /// `movement` itself, and castles are worth 0.
+    #[must_use]
+    pub fn see(&self, movement: &PseudoMove) -> i32 {
+        let us = self.turn;
+        let origin = movement.origin;
+        let destination = movement.destination;
+
+        let is_castle = matches!(movement.kind, MoveKind::Castle(_))
+            || self.castle_from_move(movement).is_some();
+        let Some(mut attacker) = self.side(us).pieces.find(origin) else {
+            return 0;
+        };
+        if is_castle {
+            return 0;
+        }
+
+        let theirs = self.side(us.opposite());
+        let mut occupancy = self
+            .side(us)
+            .occupancy
+            .with(theirs.occupancy)
+            .without(BoardMask::from(origin));
+
+        let mut gain = [0; 32];
+        gain[0] = match theirs.pieces.find(destination) {
+            Some(victim) => see_value(victim),
+            None if attacker == Piece::Pawn && theirs.en_passant == Some(destination) => {
+                let captured = destination.next_rank(theirs.side);
+                occupancy = occupancy.without(BoardMask::from(captured));
+                see_value(Piece::Pawn)
+            }
+            None => 0,
+        };
+
+        if let MoveKind::Promote(piece) = movement.kind {
+            gain[0] += see_value(piece) - see_value(Piece::Pawn);
+            attacker = piece;
+        }
+
+        let mut depth = 0;
+        let mut side = us.opposite();
+        loop {
+            let attackers = self.attackers_to_with_occupancy(destination, side, occupancy);
+            let Some((piece, square)) = self.least_valuable_attacker(side, attackers) else {
+                break;
+            };
+
+            // The king can only capture if the other side can't recapture.
+            if piece == Piece::King
+                && self.attackers_to_with_occupancy(
+                    destination,
+                    side.opposite(),
+                    occupancy.without(BoardMask::from(square)),
+                ) != BoardMask::default()
+            {
+                break;
+            }
+
+            depth += 1;
+            gain[depth] = see_value(attacker) - gain[depth - 1];
+            occupancy = occupancy.without(BoardMask::from(square));
+            attacker = piece;
+            side = side.opposite();
+        }
+
+        // Each side stops capturing if it's better off doing so.
+        while depth > 0 {
+            gain[depth - 1] = -(-gain[depth - 1]).max(gain[depth]);
+            depth -= 1;
+        }
+
+        gain[0]
+    }
+
+    /// Whether the static exchange evaluation of `movement` is at least
+    /// `threshold`. See [`BorkedBoard::see`].
+    #[inline]
+    #[must_use]
+    pub fn see_ge(&self, movement: &PseudoMove, threshold: i32) -> bool {
+        self.see(movement) >= threshold
+    }
+
+    fn least_valuable_attacker(&self, side: Side, attackers: BoardMask) -> Option<(Piece, Square)> {
+        let pieces = &self.side(side).pieces;
+        Piece::iter().find_map(|piece| {
+            let square = pieces.piece(piece).only(attackers).first()?;
+            Some((piece, square))
+        })
+    }
+}
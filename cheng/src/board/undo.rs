@@ -0,0 +1,151 @@
+use crate::{
+    board::BoardMask,
+    side_state::{CastlingRights, SidePiecesThreats, SideState},
+    BorkedBoard, PackedMove, Piece, PseudoMove, Side, Square,
+};
+
+/// Everything needed to take back a move made with [`BorkedBoard::make`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Undo {
+    movement: PseudoMove,
+    captured: Option<(Piece, Square)>,
+    white_side: SideUndo,
+    black_side: SideUndo,
+    halfmove_clock: usize,
+    fullmove_clock: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SideUndo {
+    castling_rights: CastlingRights,
+    en_passant: Option<Square>,
+    threats: BoardMask,
+    pieces_threats: SidePiecesThreats,
+    king_in_check: bool,
+    hash: u64,
+}
+
+impl SideUndo {
+    fn new(side: &SideState) -> Self {
+        Self {
+            castling_rights: side.castling_rights,
+            en_passant: side.en_passant,
+            threats: side.threats,
+            pieces_threats: side.pieces_threats.clone(),
+            king_in_check: side.king_in_check,
+            hash: side.hash,
+        }
+    }
+
+    fn restore(self, side: &mut SideState) {
+        side.castling_rights = self.castling_rights;
+        side.en_passant = self.en_passant;
+        side.threats = self.threats;
+        side.pieces_threats = self.pieces_threats;
+        side.king_in_check = self.king_in_check;
+        side.hash = self.hash;
+    }
+}
+
+impl Undo {
+    #[inline]
+    #[must_use]
+    pub fn movement(&self) -> &PseudoMove {
+        &self.movement
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn captured(&self) -> Option<(Piece, Square)> {
+        self.captured
+    }
+}
+
+impl BorkedBoard {
+    /// Returns the piece (and its square) that `movement` would capture,
+    /// taking en passant into account.
+    #[must_use]
+    pub fn captured_piece(&self, movement: &PseudoMove) -> Option<(Piece, Square)> {
+        let opposite = self.side(self.turn.opposite());
+
+        if let Some(piece) = opposite.pieces.find(movement.destination) {
+            return Some((piece, movement.destination));
+        }
+
+        let piece_is_pawn = self
+            .side(self.turn)
+            .pieces
+            .piece(Piece::Pawn)
+            .get(movement.origin);
+
+        if piece_is_pawn && opposite.en_passant == Some(movement.destination) {
+            let actual_pawn_square = movement.destination.next_rank(opposite.side);
+            return Some((Piece::Pawn, actual_pawn_square));
+        }
+
+        None
+    }
+
+    /// Feeds `movement` without checking it, returning the information needed
+    /// to take it back with [`BorkedBoard::unmake`].
+    pub fn make(&mut self, movement: &PseudoMove) -> Undo {
+        let captured = self.captured_piece(movement);
+        self.make_with_captured(movement.clone(), captured)
+    }
+
+    /// Like [`BorkedBoard::make`], but the captured piece is found from the
+    /// flags of the move instead of looking for it on every square.
+    pub fn make_packed(&mut self, movement: PackedMove) -> Undo {
+        let destination = movement.destination();
+        let opposite = self.side(self.turn.opposite());
+
+        let captured = if movement.is_en_passant() {
+            Some((Piece::Pawn, destination.next_rank(opposite.side)))
+        } else if movement.is_capture() {
+            opposite
+                .pieces
+                .find(destination)
+                .map(|piece| (piece, destination))
+        } else {
+            None
+        };
+
+        self.make_with_captured(movement.into(), captured)
+    }
+
+    fn make_with_captured(
+        &mut self,
+        movement: PseudoMove,
+        captured: Option<(Piece, Square)>,
+    ) -> Undo {
+        let undo = Undo {
+            movement,
+            captured,
+            white_side: SideUndo::new(&self.white_side),
+            black_side: SideUndo::new(&self.black_side),
+            halfmove_clock: self.halfmove_clock,
+            fullmove_clock: self.fullmove_clock,
+        };
+
+        self.feed_unchecked(&undo.movement);
+        undo
+    }
+
+    /// Takes back the last move made with [`BorkedBoard::make`]. The position
+    /// is left exactly as it was before the move.
+    pub fn unmake(&mut self, undo: Undo) {
+        self.turn = self.turn.opposite();
+
+        self.side_mut(self.turn).revert(&undo.movement);
+        if let Some((piece, square)) = undo.captured {
+            self.side_mut(self.turn.opposite()).put(square, piece);
+        }
+
+        undo.white_side.restore(self.side_mut(Side::White));
+        undo.black_side.restore(self.side_mut(Side::Black));
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_clock = undo.fullmove_clock;
+
+        debug_assert_eq!(self.hash(), self.compute_hash());
+    }
+}
@@ -0,0 +1,293 @@
+use std::{fmt::Display, iter::Peekable, str::Chars, str::FromStr};
+
+use crate::{
+    board::{FENParsingError, FenEnPassant, FenWriteOptions},
+    Board, FromIntoFen, PseudoMove, SANParseError,
+};
+
+/// A position in Extended Position Description: the first four fields of a
+/// FEN followed by operations, e.g.
+/// `r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - bm Bb5; id "ruy";`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Epd {
+    /// The position, with the clocks of the `hmvc` and `fmvn` operations. They
+    /// are 0 and 1 without them.
+    pub board: Board,
+    /// Operations in the order they were read.
+    pub operations: Vec<EpdOperation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpdOperation {
+    pub opcode: String,
+    pub operands: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EpdError {
+    MissingField,
+    /// The position, checked with [`crate::FenValidation::Strict`]. Offsets
+    /// are from the start of the FEN made of the first four fields and the
+    /// clocks.
+    InvalidFen(FENParsingError),
+    /// Opcodes start with a letter and only have letters, digits and `_`.
+    InvalidOpcode,
+    UnterminatedString,
+    InvalidClock,
+}
+
+impl Epd {
+    /// An EPD of `board` without operations. The clocks of `board` are only
+    /// written if `hmvc` and `fmvn` operations are added.
+    #[must_use]
+    pub fn new(board: Board) -> Self {
+        Self {
+            board,
+            operations: Vec::new(),
+        }
+    }
+
+    /// The operands of the first operation with `opcode`.
+    #[must_use]
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        self.operations
+            .iter()
+            .find(|operation| operation.opcode == opcode)
+            .map(|operation| operation.operands.as_slice())
+    }
+
+    /// Replaces the operands of the operation with `opcode`, or adds it at the
+    /// end if there is none.
+    pub fn set_operation(&mut self, opcode: &str, operands: Vec<String>) {
+        match self
+            .operations
+            .iter_mut()
+            .find(|operation| operation.opcode == opcode)
+        {
+            Some(operation) => operation.operands = operands,
+            None => self.operations.push(EpdOperation {
+                opcode: opcode.to_string(),
+                operands,
+            }),
+        }
+    }
+
+    /// The `id` of the position.
+    #[must_use]
+    pub fn id(&self) -> Option<&str> {
+        self.single_operand("id")
+    }
+
+    /// The comment `c0` to `c9`.
+    #[must_use]
+    pub fn comment(&self, index: u8) -> Option<&str> {
+        self.single_operand(&format!("c{index}"))
+    }
+
+    /// The best moves, `bm`. Empty without the operation.
+    pub fn best_moves(&self) -> Result<Vec<PseudoMove>, SANParseError> {
+        self.moves("bm")
+    }
+
+    /// The moves to avoid, `am`. Empty without the operation.
+    pub fn avoid_moves(&self) -> Result<Vec<PseudoMove>, SANParseError> {
+        self.moves("am")
+    }
+
+    /// The predicted variation, `pv`, each move played from the position the
+    /// previous one leads to. Empty without the operation.
+    pub fn predicted_variation(&self) -> Result<Vec<PseudoMove>, SANParseError> {
+        let mut board = self.board.inner().clone();
+        let mut moves = Vec::new();
+
+        for san in self.operation("pv").unwrap_or_default() {
+            let movement = PseudoMove::from(&board.parse_san(san)?);
+            board.make(&movement);
+            moves.push(movement);
+        }
+
+        Ok(moves)
+    }
+
+    /// The depth of the analysis, `acd`.
+    #[must_use]
+    pub fn analysis_depth(&self) -> Option<u32> {
+        self.single_operand("acd")?.parse().ok()
+    }
+
+    /// The evaluation in centipawns from the side to move's view, `ce`.
+    #[must_use]
+    pub fn centipawn_evaluation(&self) -> Option<i32> {
+        self.single_operand("ce")?.parse().ok()
+    }
+
+    /// The number of leaves `depth` plies below the position, `D1` to `Dn`.
+    #[must_use]
+    pub fn perft(&self, depth: usize) -> Option<usize> {
+        self.single_operand(&format!("D{depth}"))?.parse().ok()
+    }
+
+    fn single_operand(&self, opcode: &str) -> Option<&str> {
+        match self.operation(opcode)? {
+            [operand] => Some(operand),
+            _ => None,
+        }
+    }
+
+    fn moves(&self, opcode: &str) -> Result<Vec<PseudoMove>, SANParseError> {
+        self.operation(opcode)
+            .unwrap_or_default()
+            .iter()
+            .map(|san| Ok(PseudoMove::from(&self.board.parse_san(san)?)))
+            .collect()
+    }
+}
+
+impl FromStr for Epd {
+    type Err = EpdError;
+
+    fn from_str(epd: &str) -> Result<Self, Self::Err> {
+        let mut rest = epd.trim_start();
+        let mut fields = Vec::new();
+        for _ in 0..4 {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(EpdError::MissingField);
+            }
+            fields.push(&rest[..end]);
+            rest = rest[end..].trim_start();
+        }
+
+        let operations = parse_operations(rest)?;
+        let clock = |opcode, default| {
+            let operation = operations
+                .iter()
+                .find(|operation| operation.opcode == opcode);
+            match operation.map(|operation| operation.operands.as_slice()) {
+                None => Ok(default),
+                Some([clock]) => clock.parse().map_err(|_| EpdError::InvalidClock),
+                Some(_) => Err(EpdError::InvalidClock),
+            }
+        };
+
+        let fen = format!(
+            "{} {} {}",
+            fields.join(" "),
+            clock("hmvc", 0)?,
+            clock("fmvn", 1)?
+        );
+        let board = Board::from_fen(&fen).map_err(EpdError::InvalidFen)?;
+
+        Ok(Self { board, operations })
+    }
+}
+
+impl Display for Epd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The en passant square is kept as it was read, even when no capture is
+        // possible.
+        let options = FenWriteOptions {
+            en_passant: FenEnPassant::Always,
+            move_counters: false,
+            ..FenWriteOptions::default()
+        };
+        write!(f, "{}", self.board.as_fen_with(&options))?;
+
+        for operation in &self.operations {
+            write!(f, " {operation}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for EpdOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Comments and ids are strings, even if they are a single word.
+        let string = self.opcode == "id"
+            || (self.opcode.len() == 2
+                && self.opcode.starts_with(['c', 'v'])
+                && self.opcode.ends_with(|chr: char| chr.is_ascii_digit()));
+
+        write!(f, "{}", self.opcode)?;
+        for operand in &self.operands {
+            let quote = string
+                || operand.is_empty()
+                || operand.contains(|chr: char| chr.is_whitespace() || chr == ';');
+
+            if quote {
+                write!(f, " \"{operand}\"")?;
+            } else {
+                write!(f, " {operand}")?;
+            }
+        }
+
+        write!(f, ";")
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|chr| chr.is_whitespace()).is_some() {}
+}
+
+/// Reads operations until the end of `operations`. The `;` of the last one
+/// may be missing.
+fn parse_operations(operations: &str) -> Result<Vec<EpdOperation>, EpdError> {
+    let mut chars = operations.chars().peekable();
+    let mut result = Vec::new();
+
+    loop {
+        skip_whitespace(&mut chars);
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut opcode = String::new();
+        while let Some(chr) = chars.next_if(|chr| !chr.is_whitespace() && *chr != ';') {
+            opcode.push(chr);
+        }
+
+        let valid = opcode.starts_with(|chr: char| chr.is_ascii_alphabetic())
+            && opcode
+                .chars()
+                .all(|chr| chr.is_ascii_alphanumeric() || chr == '_');
+        if !valid {
+            return Err(EpdError::InvalidOpcode);
+        }
+
+        let mut operands = Vec::new();
+        loop {
+            skip_whitespace(&mut chars);
+            match chars.peek() {
+                None => break,
+                Some(';') => {
+                    chars.next();
+                    break;
+                }
+                Some('"') => {
+                    chars.next();
+                    let mut operand = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some(chr) => operand.push(chr),
+                            None => return Err(EpdError::UnterminatedString),
+                        }
+                    }
+                    operands.push(operand);
+                }
+                Some(_) => {
+                    let mut operand = String::new();
+                    while let Some(chr) = chars.next_if(|chr| !chr.is_whitespace() && *chr != ';') {
+                        operand.push(chr);
+                    }
+                    operands.push(operand);
+                }
+            }
+        }
+
+        result.push(EpdOperation { opcode, operands });
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,278 @@
+use crate::{board::TryFeedError, Board, PgnGame, PgnMove, PseudoMove, TryIntoMove, SAN};
+
+/// A game with its whole move history, which can be navigated back and forth.
+///
+/// Moves form a tree: playing a move that differs from the one that was
+/// undone starts a variation instead of discarding the old moves. The first
+/// child of a position is its main line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    /// PGN tags, kept as they were loaded.
+    tags: Vec<(String, String)>,
+    initial_board: Board,
+    nodes: Vec<GameNode>,
+    // Moves played from the initial position.
+    root_children: Vec<usize>,
+    // `None` when at the initial position.
+    current: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct GameNode {
+    movement: PseudoMove,
+    san: String,
+    // The position after the move.
+    board: Board,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    ply: usize,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(Board::default())
+    }
+}
+
+impl Game {
+    #[must_use]
+    pub fn new(initial_board: Board) -> Self {
+        Self {
+            tags: Vec::new(),
+            initial_board,
+            nodes: Vec::new(),
+            root_children: Vec::new(),
+            current: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn initial_board(&self) -> &Board {
+        &self.initial_board
+    }
+
+    /// The tags of the PGN the game was loaded from, written back by
+    /// [`Game::to_pgn`].
+    #[inline]
+    #[must_use]
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    /// The current position.
+    #[must_use]
+    pub fn board(&self) -> &Board {
+        match self.current {
+            Some(node) => &self.nodes[node].board,
+            None => &self.initial_board,
+        }
+    }
+
+    /// How many moves were played to reach the current position.
+    #[must_use]
+    pub fn ply(&self) -> usize {
+        self.current.map_or(0, |node| self.nodes[node].ply)
+    }
+
+    fn children(&self, node: Option<usize>) -> &[usize] {
+        match node {
+            Some(node) => &self.nodes[node].children,
+            None => &self.root_children,
+        }
+    }
+
+    /// Plays `movement` from the current position. If it was already played
+    /// from here, the existing move is reused, otherwise it's added as the
+    /// main line or as a new variation.
+    pub fn try_play<M>(&mut self, movement: M) -> Result<(), TryFeedError<M::Error>>
+    where
+        M: TryIntoMove,
+    {
+        let board = self.board();
+        let movement = movement
+            .try_into_move(board.inner())
+            .map_err(TryFeedError::Parsing)?;
+        let Some(legal_move) = board.validate(movement) else {
+            return Err(TryFeedError::InvalidMove);
+        };
+
+        let existing = self
+            .children(self.current)
+            .iter()
+            .find(|&&child| self.nodes[child].movement == PseudoMove::from(&legal_move));
+        if let Some(&existing) = existing {
+            self.current = Some(existing);
+            return Ok(());
+        }
+
+        let san = SAN(&legal_move, board).to_string();
+        let movement = PseudoMove::from(legal_move);
+        let mut board = board.clone();
+        board
+            .try_feed(movement.clone())
+            .map_err(|_| TryFeedError::InvalidMove)?;
+
+        let index = self.nodes.len();
+        let ply = self.ply() + 1;
+        self.nodes.push(GameNode {
+            movement,
+            san,
+            board,
+            parent: self.current,
+            children: Vec::new(),
+            ply,
+        });
+
+        match self.current {
+            Some(node) => self.nodes[node].children.push(index),
+            None => self.root_children.push(index),
+        }
+        self.current = Some(index);
+        Ok(())
+    }
+
+    /// Goes back one move. Returns `false` at the initial position.
+    pub fn undo(&mut self) -> bool {
+        let Some(node) = self.current else {
+            return false;
+        };
+
+        self.current = self.nodes[node].parent;
+        true
+    }
+
+    /// Plays again the main line move after the current position. Returns
+    /// `false` if there is none.
+    pub fn redo(&mut self) -> bool {
+        self.enter_variation(0)
+    }
+
+    /// Goes to the `ply`-th position of the current line, following the main
+    /// line past the current position. Returns `false` if the line is shorter.
+    pub fn goto(&mut self, ply: usize) -> bool {
+        let current = self.current;
+        while self.ply() > ply {
+            self.undo();
+        }
+
+        while self.ply() < ply {
+            if !self.redo() {
+                self.current = current;
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// The SAN of the moves that can be played from the current position,
+    /// the main line first.
+    #[must_use]
+    pub fn variations(&self) -> Vec<&str> {
+        self.children(self.current)
+            .iter()
+            .map(|&child| self.nodes[child].san.as_str())
+            .collect()
+    }
+
+    /// Plays the `index`-th move of [`Game::variations`]. Returns `false` if
+    /// there is no such move.
+    pub fn enter_variation(&mut self, index: usize) -> bool {
+        let Some(&child) = self.children(self.current).get(index) else {
+            return false;
+        };
+
+        self.current = Some(child);
+        true
+    }
+
+    /// The SAN of the moves of the current line: the ones leading to the
+    /// current position followed by the main line after it.
+    #[must_use]
+    pub fn san_moves(&self) -> Vec<&str> {
+        let mut moves = Vec::new();
+        let mut node = self.current;
+        while let Some(index) = node {
+            moves.push(self.nodes[index].san.as_str());
+            node = self.nodes[index].parent;
+        }
+        moves.reverse();
+
+        let mut node = self.current;
+        while let Some(&child) = self.children(node).first() {
+            moves.push(self.nodes[child].san.as_str());
+            node = Some(child);
+        }
+
+        moves
+    }
+
+    /// Loads the tags, moves and variations of `pgn`, leaving the game at its
+    /// initial position. Fails if any of the moves is illegal.
+    pub fn try_from_pgn(
+        pgn: &PgnGame,
+    ) -> Result<Self, TryFeedError<<PseudoMove as TryIntoMove>::Error>> {
+        fn add_line(
+            game: &mut Game,
+            moves: &[PgnMove],
+        ) -> Result<(), TryFeedError<<PseudoMove as TryIntoMove>::Error>> {
+            let start = game.current;
+            for pgn_move in moves {
+                let before = game.current;
+                game.try_play(pgn_move.movement.clone())?;
+
+                for variation in &pgn_move.variations {
+                    let after = game.current;
+                    game.current = before;
+                    add_line(game, variation)?;
+                    game.current = after;
+                }
+            }
+            game.current = start;
+            Ok(())
+        }
+
+        let mut game = Game::new(pgn.initial_board.clone());
+        game.tags.clone_from(&pgn.tags);
+        add_line(&mut game, &pgn.moves)?;
+        Ok(game)
+    }
+
+    /// Exports the whole move tree. The result is taken from the last
+    /// position of the main line.
+    #[must_use]
+    pub fn to_pgn(&self) -> PgnGame {
+        fn line<'a>(game: &'a Game, mut children: &'a [usize]) -> Vec<PgnMove> {
+            let mut moves = Vec::new();
+            while let Some((&main, variations)) = children.split_first() {
+                let node = &game.nodes[main];
+                let mut pgn_move = PgnMove::new(node.movement.clone(), node.san.clone());
+                for variation in variations {
+                    pgn_move
+                        .variations
+                        .push(line(game, std::slice::from_ref(variation)));
+                }
+
+                moves.push(pgn_move);
+                children = &node.children;
+            }
+            moves
+        }
+
+        let mut pgn = PgnGame::new(self.initial_board.clone());
+        pgn.tags.clone_from(&self.tags);
+        pgn.moves = line(self, &self.root_children);
+
+        let mut last = None;
+        while let Some(&child) = self.children(last).first() {
+            last = Some(child);
+        }
+        pgn.result = match last {
+            Some(node) => self.nodes[node].board.result().into(),
+            None => self.initial_board.result().into(),
+        };
+
+        pgn
+    }
+}
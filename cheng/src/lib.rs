@@ -1,20 +1,37 @@
 #![cfg_attr(feature = "simd", feature(portable_simd))]
 
 mod board;
+mod epd;
 mod fen;
+mod game;
 pub mod movegen;
 mod movement;
+mod perft;
+mod pgn;
 mod pieces;
 mod side_state;
 mod sides;
 mod square;
-
-use movegen::{Bishop, PieceExt, Rook};
+mod zobrist;
 
 pub use crate::{
-    board::{Board, BoardMask, BorkedBoard, FENParsingError, GameResult, PseudoMoveGenerator},
+    board::{
+        Board, BoardMask, BorkedBoard, ClaimDrawError, DrawReason, FENParsingError,
+        FENParsingErrorKind, FenCastling, FenEnPassant, FenField, FenValidation, FenWriteOptions,
+        GameResult, GenerationMode, PseudoMoveGenerator, TryFeedError, Undo,
+    },
+    epd::{Epd, EpdError, EpdOperation},
     fen::FromIntoFen,
-    movement::{Castle, LegalMove, MoveKind, MoveParseError, PseudoMove, SAN},
+    game::Game,
+    movement::{
+        Castle, LegalMove, MoveKind, MoveList, MoveParseError, PackedMove, PseudoMove,
+        SANParseError, TryIntoMove, SAN,
+    },
+    perft::{Perft, PerftStats},
+    pgn::{
+        PgnError, PgnErrorKind, PgnGame, PgnMove, PgnReader, PgnResult, PgnWriteOptions,
+        SEVEN_TAG_ROSTER,
+    },
     pieces::Piece,
     side_state::CastlingRights,
     sides::Side,
@@ -37,13 +54,9 @@ impl From<SidedPiece> for char {
 #[cfg(test)]
 mod test;
 
+/// Builds the move tables of the sliding pieces, which otherwise happens the
+/// first time they are needed. Calling it is optional.
 pub fn init() {
-    // TODO: Make these automatic.
-    Rook::init();
-    Bishop::init();
-}
-
-pub mod internal {
-    pub use crate::movegen::BISHOP_MOVES;
-    pub use crate::movegen::ROOK_MOVES;
+    movegen::rook_table();
+    movegen::bishop_table();
 }
@@ -1,7 +1,11 @@
+use std::sync::OnceLock;
+
 use self::hash::magic_hash;
 use crate::{board::BoardMask, pieces::Piece, square::Square, Side, SidedPiece};
 
 mod hash;
+#[cfg(feature = "pext")]
+pub(crate) mod pext;
 pub(crate) mod steady;
 
 #[rustfmt::skip]
@@ -10,24 +14,101 @@ mod precomputed;
 pub struct Bishop;
 
 impl Bishop {
-    pub const fn nbits() -> u32 {
-        <Self as steady::SlidingPiece>::NBITS
+    /// The squares whose occupancy changes the moves of a bishop on `square`.
+    #[inline]
+    #[must_use]
+    pub fn relevant_occupancy(square: Square) -> BoardMask {
+        precomputed::BISHOP_OCCUPANCY[square.to_index()]
+    }
+
+    /// How many moves the table of bishop moves holds.
+    #[must_use]
+    pub const fn table_size() -> usize {
+        precomputed::BISHOP_TABLE_SIZE
     }
 }
 
 pub struct Rook;
 
 impl Rook {
-    pub const fn nbits() -> u32 {
-        <Self as steady::SlidingPiece>::NBITS
+    /// The squares whose occupancy changes the moves of a rook on `square`.
+    #[inline]
+    #[must_use]
+    pub fn relevant_occupancy(square: Square) -> BoardMask {
+        precomputed::ROOK_OCCUPANCY[square.to_index()]
     }
+
+    /// How many moves the table of rook moves holds.
+    #[must_use]
+    pub const fn table_size() -> usize {
+        precomputed::ROOK_TABLE_SIZE
+    }
+}
+
+pub type RookTable = [BoardMask; Rook::table_size()];
+
+pub type BishopTable = [BoardMask; Bishop::table_size()];
+
+static ROOK_MOVES: OnceLock<Box<RookTable>> = OnceLock::new();
+
+static BISHOP_MOVES: OnceLock<Box<BishopTable>> = OnceLock::new();
+
+/// The rook moves of every square and occupancy. The moves of each square
+/// start at its offset, indexed by the magic hash of the occupancy. Built the
+/// first time it's needed.
+#[inline]
+pub fn rook_table() -> &'static RookTable {
+    ROOK_MOVES.get_or_init(|| {
+        build_sliding_table::<Rook, { Rook::table_size() }>(
+            &precomputed::ROOK_OCCUPANCY,
+            &precomputed::ROOK_MAGICS,
+            &precomputed::ROOK_BITS,
+            &precomputed::ROOK_OFFSETS,
+        )
+    })
 }
 
-pub static mut ROOK_MOVES: [[BoardMask; 1 << Rook::nbits()]; 64] =
-    [[BoardMask::const_from(0); 1 << Rook::nbits()]; 64];
+/// The bishop moves of every square and occupancy, see [`rook_table`].
+#[inline]
+pub fn bishop_table() -> &'static BishopTable {
+    BISHOP_MOVES.get_or_init(|| {
+        build_sliding_table::<Bishop, { Bishop::table_size() }>(
+            &precomputed::BISHOP_OCCUPANCY,
+            &precomputed::BISHOP_MAGICS,
+            &precomputed::BISHOP_BITS,
+            &precomputed::BISHOP_OFFSETS,
+        )
+    })
+}
+
+fn build_sliding_table<P: steady::SlidingPiece, const N: usize>(
+    relevant_occupancies: &[BoardMask; 64],
+    magics: &[u64; 64],
+    bits: &[u32; 64],
+    offsets: &[usize; 64],
+) -> Box<[BoardMask; N]> {
+    // Built on the heap, the table is too big for the stack.
+    let mut table: Box<[BoardMask; N]> = vec![BoardMask::default(); N]
+        .into_boxed_slice()
+        .try_into()
+        .unwrap();
+
+    for square in Square::iter_all() {
+        let index = square.to_index();
+        let relevant_occupancy = relevant_occupancies[index];
+        for i in 0..relevant_occupancy.variations() {
+            let occupancy = relevant_occupancy.variation(i);
+            let hash = offsets[index] + magic_hash(magics[index], occupancy, bits[index]);
 
-pub static mut BISHOP_MOVES: [[BoardMask; 1 << Bishop::nbits()]; 64] =
-    [[BoardMask::const_from(0); 1 << Bishop::nbits()]; 64];
+            let moves = P::moves(square, occupancy);
+            let collision = table[hash] != BoardMask::default() && table[hash] != moves;
+            assert!(!collision);
+            table[hash] = moves;
+        }
+    }
+
+    table
+}
 
 pub fn moves(
     SidedPiece(side, piece): SidedPiece,
@@ -105,9 +186,74 @@ pub(crate) fn pawn_threats(side: Side, square: Square) -> BoardMask {
     }
 }
 
-pub trait PieceExt {
-    fn init() {}
+const A_FILE: u64 = 0x0101_0101_0101_0101;
+const H_FILE: u64 = A_FILE << 7;
+
+/// How much the square index of a pawn of `side` changes when it's pushed.
+#[inline]
+#[must_use]
+pub const fn pawn_push_offset(side: Side) -> i32 {
+    match side {
+        Side::White => 8,
+        Side::Black => -8,
+    }
+}
 
+/// How much the square index of a pawn of `side` changes when it captures
+/// towards the A file (`west`) or the H file.
+#[inline]
+#[must_use]
+pub const fn pawn_capture_offset(side: Side, west: bool) -> i32 {
+    pawn_push_offset(side) + if west { -1 } else { 1 }
+}
+
+#[inline]
+fn shift(mask: BoardMask, offset: i32) -> BoardMask {
+    let mask = u64::from(mask);
+    if offset >= 0 {
+        BoardMask::from(mask << offset)
+    } else {
+        BoardMask::from(mask >> -offset)
+    }
+}
+
+/// The squares every pawn of `side` in `pawns` can be pushed to, one rank.
+#[inline]
+#[must_use]
+pub fn pawn_pushes(side: Side, pawns: BoardMask, empty: BoardMask) -> BoardMask {
+    pawns.push_rank(side).only(empty)
+}
+
+/// The squares the pawns of `side` still on their starting rank can be pushed
+/// to, two ranks.
+#[inline]
+#[must_use]
+pub fn pawn_double_pushes(side: Side, pawns: BoardMask, empty: BoardMask) -> BoardMask {
+    let fourth_rank = match side {
+        Side::White => BoardMask::from(0x0000_0000_ff00_0000),
+        Side::Black => BoardMask::from(0x0000_00ff_0000_0000),
+    };
+
+    pawn_pushes(side, pawn_pushes(side, pawns, empty), empty).only(fourth_rank)
+}
+
+/// The squares attacked by the pawns of `side` in `pawns` towards the A file
+/// (`west`) or the H file.
+#[inline]
+#[must_use]
+pub fn pawn_attacks_towards(side: Side, pawns: BoardMask, west: bool) -> BoardMask {
+    let wrapped = if west { H_FILE } else { A_FILE };
+    shift(pawns, pawn_capture_offset(side, west)).without(BoardMask::from(wrapped))
+}
+
+/// The squares attacked by all the pawns of `side` in `pawns`.
+#[inline]
+#[must_use]
+pub fn pawn_attacks(side: Side, pawns: BoardMask) -> BoardMask {
+    pawn_attacks_towards(side, pawns, true).with(pawn_attacks_towards(side, pawns, false))
+}
+
+pub trait PieceExt {
     fn moves(square: Square, friendly: BoardMask, opposite: BoardMask) -> BoardMask {
         Self::threats(square, friendly, opposite).without(friendly)
     }
@@ -132,67 +278,49 @@ impl PieceExt for Knight {
 }
 
 impl PieceExt for Rook {
-    fn init() {
-        for square in Square::iter_all() {
-            let index = square.to_index();
-            let relevant_occupancy = precomputed::ROOK_OCCUPANCY[index];
-            let occupancy_variations = relevant_occupancy.variations();
-            let magic = precomputed::ROOK_MAGICS[index];
-            for i in 0..occupancy_variations {
-                let occupancy = relevant_occupancy.variation(i);
-                let hash = magic_hash(magic, occupancy, Rook::nbits());
-
-                unsafe {
-                    let moves = <Rook as steady::SlidingPiece>::moves(square, occupancy);
-                    let collision = ROOK_MOVES[index][hash] != BoardMask::default()
-                        && ROOK_MOVES[index][hash] != moves;
-                    assert!(!collision);
-                    ROOK_MOVES[index][hash] = moves;
-                }
-            }
-        }
-    }
-
     fn threats(square: Square, friendly: BoardMask, opposite: BoardMask) -> BoardMask {
-        let index = square.to_index();
-        let occupancy = precomputed::ROOK_OCCUPANCY[index].only(friendly.with(opposite));
-        let hash = magic_hash(precomputed::ROOK_MAGICS[index], occupancy, Rook::nbits());
+        #[cfg(feature = "pext")]
+        if pext::is_available() {
+            return pext::rook_threats(square, friendly.with(opposite));
+        }
 
-        unsafe { ROOK_MOVES[index][hash] }
+        magic_rook_threats(square, friendly.with(opposite))
     }
 }
 
 impl PieceExt for Bishop {
-    fn init() {
-        for square in Square::iter_all() {
-            let index = square.to_index();
-            let relevant_occupancy = precomputed::BISHOP_OCCUPANCY[index];
-            let occupancy_variations = relevant_occupancy.variations();
-            let magic = precomputed::BISHOP_MAGICS[index];
-            for i in 0..occupancy_variations {
-                let occupancy = relevant_occupancy.variation(i);
-                let hash = magic_hash(magic, occupancy, Bishop::nbits());
-
-                unsafe {
-                    let moves = <Bishop as steady::SlidingPiece>::moves(square, occupancy);
-                    let collision = BISHOP_MOVES[index][hash] != BoardMask::default()
-                        && BISHOP_MOVES[index][hash] != moves;
-                    assert!(!collision);
-                    BISHOP_MOVES[index][hash] = moves;
-                }
-            }
+    fn threats(square: Square, friendly: BoardMask, opposite: BoardMask) -> BoardMask {
+        #[cfg(feature = "pext")]
+        if pext::is_available() {
+            return pext::bishop_threats(square, friendly.with(opposite));
         }
-    }
 
-    fn threats(square: Square, friendly: BoardMask, opposite: BoardMask) -> BoardMask {
-        let index = square.to_index();
-        let occupancy = precomputed::BISHOP_OCCUPANCY[index].only(friendly.with(opposite));
-        let hash = magic_hash(
-            precomputed::BISHOP_MAGICS[index],
-            occupancy,
-            Bishop::nbits(),
-        );
-
-        unsafe { BISHOP_MOVES[index][hash] }
+        magic_bishop_threats(square, friendly.with(opposite))
     }
 }
+
+#[inline]
+pub(crate) fn magic_rook_threats(square: Square, occupancy: BoardMask) -> BoardMask {
+    let index = square.to_index();
+    let occupancy = precomputed::ROOK_OCCUPANCY[index].only(occupancy);
+    let hash = magic_hash(
+        precomputed::ROOK_MAGICS[index],
+        occupancy,
+        precomputed::ROOK_BITS[index],
+    );
+
+    rook_table()[precomputed::ROOK_OFFSETS[index] + hash]
+}
+
+#[inline]
+pub(crate) fn magic_bishop_threats(square: Square, occupancy: BoardMask) -> BoardMask {
+    let index = square.to_index();
+    let occupancy = precomputed::BISHOP_OCCUPANCY[index].only(occupancy);
+    let hash = magic_hash(
+        precomputed::BISHOP_MAGICS[index],
+        occupancy,
+        precomputed::BISHOP_BITS[index],
+    );
+
+    bishop_table()[precomputed::BISHOP_OFFSETS[index] + hash]
+}
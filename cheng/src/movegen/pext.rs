@@ -0,0 +1,109 @@
+//! Sliding piece moves indexed with the BMI2 `pext` instruction, which packs
+//! the relevant occupancy bits without needing magics. Only used when the CPU
+//! has BMI2, see [`is_available`].
+
+use std::sync::OnceLock;
+
+use super::{precomputed, steady::SlidingPiece, Bishop, BishopTable, Rook, RookTable};
+use crate::{board::BoardMask, square::Square};
+
+static ROOK_MOVES: OnceLock<Box<RookTable>> = OnceLock::new();
+
+static BISHOP_MOVES: OnceLock<Box<BishopTable>> = OnceLock::new();
+
+/// Whether the CPU can run `pext`.
+#[inline]
+pub fn is_available() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        std::arch::is_x86_feature_detected!("bmi2")
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+#[inline]
+pub fn rook_threats(square: Square, occupancy: BoardMask) -> BoardMask {
+    let index = square.to_index();
+    let table = ROOK_MOVES.get_or_init(|| {
+        build_table::<Rook, { Rook::table_size() }>(
+            &precomputed::ROOK_OCCUPANCY,
+            &precomputed::ROOK_OFFSETS,
+        )
+    });
+
+    let hash = pext(occupancy, precomputed::ROOK_OCCUPANCY[index]);
+    table[precomputed::ROOK_OFFSETS[index] + hash]
+}
+
+#[inline]
+pub fn bishop_threats(square: Square, occupancy: BoardMask) -> BoardMask {
+    let index = square.to_index();
+    let table = BISHOP_MOVES.get_or_init(|| {
+        build_table::<Bishop, { Bishop::table_size() }>(
+            &precomputed::BISHOP_OCCUPANCY,
+            &precomputed::BISHOP_OFFSETS,
+        )
+    });
+
+    let hash = pext(occupancy, precomputed::BISHOP_OCCUPANCY[index]);
+    table[precomputed::BISHOP_OFFSETS[index] + hash]
+}
+
+fn build_table<P: SlidingPiece, const N: usize>(
+    relevant_occupancies: &[BoardMask; 64],
+    offsets: &[usize; 64],
+) -> Box<[BoardMask; N]> {
+    let mut table: Box<[BoardMask; N]> = vec![BoardMask::default(); N]
+        .into_boxed_slice()
+        .try_into()
+        .unwrap();
+
+    for square in Square::iter_all() {
+        let index = square.to_index();
+        let relevant_occupancy = relevant_occupancies[index];
+        // The variations are numbered the same way `pext` packs them.
+        for i in 0..relevant_occupancy.variations() {
+            let occupancy = relevant_occupancy.variation(i);
+            table[offsets[index] + i] = P::moves(square, occupancy);
+        }
+    }
+
+    table
+}
+
+/// The bits of `value` selected by `mask`, packed into the lowest bits.
+#[inline]
+pub(crate) fn pext(value: BoardMask, mask: BoardMask) -> usize {
+    let (value, mask) = (u64::from(value), u64::from(mask));
+
+    #[cfg(target_arch = "x86_64")]
+    if is_available() {
+        // SAFETY: The CPU supports BMI2.
+        return unsafe { pext_bmi2(value, mask) } as usize;
+    }
+
+    pext_fallback(value, mask) as usize
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+unsafe fn pext_bmi2(value: u64, mask: u64) -> u64 {
+    std::arch::x86_64::_pext_u64(value, mask)
+}
+
+pub(crate) fn pext_fallback(value: u64, mut mask: u64) -> u64 {
+    let mut result = 0;
+    let mut bit = 1;
+    while mask != 0 {
+        if value & mask & mask.wrapping_neg() != 0 {
+            result |= bit;
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+    result
+}
@@ -2,6 +2,8 @@ use super::{Bishop, Rook};
 
 use crate::{board::BoardMask, square::Square};
 
+// Only used by `relevant_occupancy`, which is only needed by build.rs and tests.
+#[allow(dead_code)]
 fn filter_squares<F>(discriminant: F) -> BoardMask
 where
     F: Fn(Square) -> bool,
@@ -16,22 +18,16 @@ where
 }
 
 pub trait SlidingPiece {
-    const NBITS: u32;
-
+    #[allow(dead_code)]
     fn relevant_occupancy(square: Square) -> BoardMask;
     fn moves(square: Square, occupancy: BoardMask) -> BoardMask;
 }
 
 impl SlidingPiece for Rook {
-    #[cfg(feature = "low_nbits")]
-    const NBITS: u32 = 12;
-
-    #[cfg(not(feature = "low_nbits"))]
-    const NBITS: u32 = 14;
-
     fn relevant_occupancy(square: Square) -> BoardMask {
         filter_squares(|target| match (target.rank(), target.file()) {
             (0 | 7, 0 | 7) => false,
+            _ if target == square => false,
             (_, 0 | 7) => target.file::<usize>() == square.file(),
             (0 | 7, _) => target.rank::<usize>() == square.rank(),
             (rank, file) => (rank == square.rank()) ^ (file == square.file()),
@@ -79,12 +75,6 @@ impl SlidingPiece for Rook {
 }
 
 impl SlidingPiece for Bishop {
-    #[cfg(feature = "low_nbits")]
-    const NBITS: u32 = 9;
-
-    #[cfg(not(feature = "low_nbits"))]
-    const NBITS: u32 = 14;
-
     fn relevant_occupancy(square: Square) -> BoardMask {
         let sqrank = square.rank::<i32>();
         let sqfile = square.file::<i32>();
@@ -1,21 +1,101 @@
-use super::{LegalMove, MoveKind, PseudoMove};
-use crate::Board;
+use super::{Castle, LegalMove, MoveKind, PseudoMove};
+use crate::{pieces::Piece, square::Square, Board, GameResult};
 
-use std::fmt::Display;
+use std::fmt::{Display, Write};
 
+fn file_char(square: Square) -> char {
+    char::from(b'a' + square.file::<u8>())
+}
+
+/// Displays a move of `Board` in Standard Algebraic Notation.
 pub struct SAN<'a>(pub &'a LegalMove<'a>, pub &'a Board);
 
+impl SAN<'_> {
+    fn write_disambiguation(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        piece: Piece,
+    ) -> std::fmt::Result {
+        let Self(movement, board) = self;
+        let pieces = &board.inner().side(board.turn()).pieces;
+
+        let mut ambiguous = false;
+        let mut same_file = false;
+        let mut same_rank = false;
+        for other in board.moves() {
+            if other.destination != movement.destination
+                || other.origin == movement.origin
+                || pieces.find(other.origin) != Some(piece)
+            {
+                continue;
+            }
+
+            ambiguous = true;
+            same_file |= other.origin.file::<usize>() == movement.origin.file::<usize>();
+            same_rank |= other.origin.rank::<usize>() == movement.origin.rank::<usize>();
+        }
+
+        if !ambiguous {
+            Ok(())
+        } else if !same_file {
+            f.write_char(file_char(movement.origin))
+        } else if !same_rank {
+            write!(f, "{}", movement.origin.rank::<usize>() + 1)
+        } else {
+            write!(f, "{:?}", movement.origin)
+        }
+    }
+}
+
 impl Display for SAN<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let piece = self
-            .1
+        let Self(movement, board) = self;
+        let piece = board
             .inner()
-            .side(self.1.turn())
+            .side(board.turn())
             .pieces
-            .find(self.0.origin)
+            .find(movement.origin)
             .unwrap();
-        write!(f, "{}", char::from(piece).to_uppercase())?;
-        self.0.fmt(f)
+
+        match movement.kind {
+            MoveKind::Castle(Castle::KingSide) => f.write_str("O-O")?,
+            MoveKind::Castle(Castle::QueenSide) => f.write_str("O-O-O")?,
+            MoveKind::Move | MoveKind::Promote(_) => {
+                let is_capture = board
+                    .inner()
+                    .captured_piece(&PseudoMove::from(*movement))
+                    .is_some();
+
+                if piece == Piece::Pawn {
+                    if is_capture {
+                        f.write_char(file_char(movement.origin))?;
+                    }
+                } else {
+                    f.write_char(char::from(piece).to_ascii_uppercase())?;
+                    self.write_disambiguation(f, piece)?;
+                }
+
+                if is_capture {
+                    f.write_char('x')?;
+                }
+
+                write!(f, "{:?}", movement.destination)?;
+
+                if let MoveKind::Promote(promotion) = movement.kind {
+                    write!(f, "={}", char::from(promotion).to_ascii_uppercase())?;
+                }
+            }
+        }
+
+        let mut after = (*board).clone();
+        after.feed((*movement).clone());
+        if let GameResult::Checkmate { .. } = after.result() {
+            f.write_char('#')
+        } else if after.inner().side(after.turn()).king_in_check {
+            f.write_char('+')
+        } else {
+            Ok(())
+        }
     }
 }
 
@@ -0,0 +1,134 @@
+use std::{
+    fmt::Debug,
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+};
+
+use super::PackedMove;
+
+/// A list of moves that lives on the stack, big enough for the moves of any
+/// position.
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [MaybeUninit<PackedMove>; MoveList::CAPACITY],
+    len: usize,
+}
+
+impl MoveList {
+    pub const CAPACITY: usize = 256;
+
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            moves: [const { MaybeUninit::uninit() }; Self::CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// # Panics
+    ///
+    /// If the list is full.
+    #[inline]
+    pub fn push(&mut self, movement: PackedMove) {
+        assert!(self.len < Self::CAPACITY, "move list is full");
+        self.moves[self.len].write(movement);
+        self.len += 1;
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for MoveList {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [PackedMove];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The first `len` moves have been written.
+        unsafe { std::slice::from_raw_parts(self.moves.as_ptr().cast(), self.len) }
+    }
+}
+
+impl DerefMut for MoveList {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: The first `len` moves have been written.
+        unsafe { std::slice::from_raw_parts_mut(self.moves.as_mut_ptr().cast(), self.len) }
+    }
+}
+
+impl Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for MoveList {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for MoveList {}
+
+impl Extend<PackedMove> for MoveList {
+    fn extend<T: IntoIterator<Item = PackedMove>>(&mut self, iter: T) {
+        for movement in iter {
+            self.push(movement);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a PackedMove;
+    type IntoIter = std::slice::Iter<'a, PackedMove>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for MoveList {
+    type Item = PackedMove;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            list: self,
+            index: 0,
+        }
+    }
+}
+
+pub struct IntoIter {
+    list: MoveList,
+    index: usize,
+}
+
+impl Iterator for IntoIter {
+    type Item = PackedMove;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let movement = *self.list.get(self.index)?;
+        self.index += 1;
+        Some(movement)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.list.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
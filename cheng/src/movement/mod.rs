@@ -1,14 +1,24 @@
 use std::marker::PhantomData;
 
 pub mod parsing;
-pub use parsing::MoveParseError;
+pub use parsing::{MoveParseError, TryIntoMove};
+
+mod san;
+pub use san::SANParseError;
 
 mod display;
 pub use display::SAN;
 
-use crate::{board::BoardMask, pieces::Piece, square::Square, BorkedBoard, Side};
+mod packed;
+pub use packed::PackedMove;
+
+pub mod list;
+pub use list::MoveList;
+
+use crate::{pieces::Piece, square::Square, BorkedBoard, Side};
 
-// TODO: Implement `PseudoMove` as a `std::num::NonZeroU16`.
+/// A move as given by the user. See [`PackedMove`] for the compact version
+/// produced by move generation.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PseudoMove {
     pub origin: Square,
@@ -25,7 +35,10 @@ pub struct LegalMove<'a> {
 }
 
 impl<'a> LegalMove<'a> {
-    pub unsafe fn unchecked_new(pseudo_move: PseudoMove, _: &'a BorkedBoard) -> LegalMove<'a> {
+    /// # Safety
+    ///
+    /// The caller must guarantee that `pseudo_move` is legal in `board`.
+    pub unsafe fn unchecked_new(pseudo_move: PseudoMove, _board: &'a BorkedBoard) -> LegalMove<'a> {
         LegalMove {
             origin: pseudo_move.origin,
             destination: pseudo_move.destination,
@@ -35,15 +48,10 @@ impl<'a> LegalMove<'a> {
     }
 
     pub fn new<'b>(mut pseudo_move: PseudoMove, board: &'b BorkedBoard) -> Option<LegalMove<'a>> {
-        let moved_piece_is_king = board
-            .side(board.turn)
-            .pieces
-            .piece(Piece::King)
-            .get(pseudo_move.origin);
-        if moved_piece_is_king {
-            if let Some(c) = Castle::move_could_be_castle(board.turn, &pseudo_move) {
-                pseudo_move.kind = MoveKind::Castle(c);
-            }
+        // Castles can be written both as the king moving two squares and as
+        // the king taking its own rook, so use the board's encoding.
+        if let Some(castle) = board.castle_from_move(&pseudo_move) {
+            pseudo_move = board.castle_move(board.turn, castle);
         }
 
         if board.does_move_bork(pseudo_move.clone()) {
@@ -89,31 +97,10 @@ pub enum Castle {
 }
 
 impl Castle {
+    /// Square of the castling rook in standard chess. In Chess960 it is
+    /// tracked by [`crate::side_state::SideState::castling_rook_square`].
     #[must_use]
-    pub fn move_could_be_castle(side: Side, movement: &PseudoMove) -> Option<Castle> {
-        let origin_matches_castle = movement.origin == Self::king_square_before_castle(side);
-
-        let destination_matches_king_side_castle =
-            movement.destination == Self::king_square_after_castle(Castle::KingSide, side);
-
-        let destination_matches_queen_side_castle =
-            movement.destination == Self::king_square_after_castle(Castle::QueenSide, side);
-
-        assert!(!destination_matches_king_side_castle || !destination_matches_queen_side_castle);
-
-        if !origin_matches_castle {
-            None
-        } else if destination_matches_king_side_castle {
-            Some(Castle::KingSide)
-        } else if destination_matches_queen_side_castle {
-            Some(Castle::QueenSide)
-        } else {
-            None
-        }
-    }
-
-    #[must_use]
-    pub fn rook_square_before_castle(self, side: Side) -> Square {
+    pub const fn standard_rook_square(self, side: Side) -> Square {
         use crate::prelude::*;
         match (side, self) {
             (Side::White, Castle::KingSide) => H1,
@@ -123,6 +110,15 @@ impl Castle {
         }
     }
 
+    #[must_use]
+    pub const fn standard_king_square(side: Side) -> Square {
+        use crate::prelude::*;
+        match side {
+            Side::White => E1,
+            Side::Black => E8,
+        }
+    }
+
     #[must_use]
     pub const fn rook_square_after_castle(self, side: Side) -> Square {
         use crate::prelude::*;
@@ -134,15 +130,6 @@ impl Castle {
         }
     }
 
-    #[must_use]
-    pub const fn king_square_before_castle(side: Side) -> Square {
-        use crate::prelude::*;
-        match side {
-            Side::White => E1,
-            Side::Black => E8,
-        }
-    }
-
     #[must_use]
     pub const fn king_square_after_castle(self, side: Side) -> Square {
         use crate::prelude::*;
@@ -153,25 +140,4 @@ impl Castle {
             (Side::Black, Castle::QueenSide) => C8,
         }
     }
-
-    #[must_use]
-    pub fn relevant_square_occupancy(self, side: Side) -> BoardMask {
-        use crate::prelude::*;
-        match (side, self) {
-            (side, Castle::KingSide) => self.relevant_square_threats(side),
-            (Side::White, Castle::QueenSide) => BoardMask::from([B1, C1, D1]),
-            (Side::Black, Castle::QueenSide) => BoardMask::from([B8, C8, D8]),
-        }
-    }
-
-    #[must_use]
-    pub fn relevant_square_threats(self, side: Side) -> BoardMask {
-        use crate::prelude::*;
-        match (side, self) {
-            (Side::White, Castle::KingSide) => BoardMask::from([F1, G1]),
-            (Side::Black, Castle::KingSide) => BoardMask::from([F8, G8]),
-            (Side::White, Castle::QueenSide) => BoardMask::from([C1, D1]),
-            (Side::Black, Castle::QueenSide) => BoardMask::from([C8, D8]),
-        }
-    }
 }
@@ -0,0 +1,198 @@
+use std::{fmt::Display, num::NonZeroU16};
+
+use super::{Castle, MoveKind, PseudoMove};
+use crate::{BorkedBoard, Piece, Square};
+
+/// A move packed in 16 bits: six for the origin, six for the destination and
+/// four of flags telling the kind of move. It's what move generation produces,
+/// use [`PseudoMove`] to work with the move.
+///
+/// No move goes from A1 to A1, so the value is never zero and
+/// `Option<PackedMove>` takes 16 bits as well.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct PackedMove(NonZeroU16);
+
+const QUIET: u16 = 0;
+const DOUBLE_PAWN_PUSH: u16 = 1;
+const KING_SIDE_CASTLE: u16 = 2;
+const QUEEN_SIDE_CASTLE: u16 = 3;
+const CAPTURE: u16 = 4;
+const EN_PASSANT: u16 = 5;
+// The two lowest bits are the promoted piece.
+const PROMOTION: u16 = 8;
+
+const PROMOTION_PIECES: [Piece; 4] = [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen];
+
+impl PackedMove {
+    #[inline]
+    const fn new(origin: Square, destination: Square, flags: u16) -> Self {
+        let value = origin.to_index() as u16 | (destination.to_index() as u16) << 6 | flags << 12;
+        match NonZeroU16::new(value) {
+            Some(value) => Self(value),
+            None => panic!("a move can't go from a square to itself"),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn quiet(origin: Square, destination: Square) -> Self {
+        Self::new(origin, destination, QUIET)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn capture(origin: Square, destination: Square) -> Self {
+        Self::new(origin, destination, CAPTURE)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn double_pawn_push(origin: Square, destination: Square) -> Self {
+        Self::new(origin, destination, DOUBLE_PAWN_PUSH)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn en_passant(origin: Square, destination: Square) -> Self {
+        Self::new(origin, destination, EN_PASSANT)
+    }
+
+    /// `origin` is the king's square and `destination` is encoded as in
+    /// [`BorkedBoard::castle_move`].
+    #[inline]
+    #[must_use]
+    pub const fn castle(origin: Square, destination: Square, castle: Castle) -> Self {
+        let flags = match castle {
+            Castle::KingSide => KING_SIDE_CASTLE,
+            Castle::QueenSide => QUEEN_SIDE_CASTLE,
+        };
+        Self::new(origin, destination, flags)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn promotion(origin: Square, destination: Square, piece: Piece, capture: bool) -> Self {
+        let piece = PROMOTION_PIECES
+            .iter()
+            .position(|&promotable| promotable == piece)
+            .expect("can't promote to this piece") as u16;
+        let capture = if capture { CAPTURE } else { 0 };
+        Self::new(origin, destination, PROMOTION | capture | piece)
+    }
+
+    #[inline]
+    const fn flags(self) -> u16 {
+        self.0.get() >> 12
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn origin(self) -> Square {
+        Square::from_index((self.0.get() & 0x3f) as usize)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn destination(self) -> Square {
+        Square::from_index((self.0.get() >> 6 & 0x3f) as usize)
+    }
+
+    /// Whether the move captures a piece, en passant included.
+    #[inline]
+    #[must_use]
+    pub const fn is_capture(self) -> bool {
+        self.flags() & CAPTURE != 0
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_en_passant(self) -> bool {
+        self.flags() == EN_PASSANT
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_double_pawn_push(self) -> bool {
+        self.flags() == DOUBLE_PAWN_PUSH
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn promotion_piece(self) -> Option<Piece> {
+        if self.flags() & PROMOTION == 0 {
+            None
+        } else {
+            Some(PROMOTION_PIECES[(self.flags() & 0b11) as usize])
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn castle_kind(self) -> Option<Castle> {
+        match self.flags() {
+            KING_SIDE_CASTLE => Some(Castle::KingSide),
+            QUEEN_SIDE_CASTLE => Some(Castle::QueenSide),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn kind(self) -> MoveKind {
+        match (self.promotion_piece(), self.castle_kind()) {
+            (Some(piece), _) => MoveKind::Promote(piece),
+            (None, Some(castle)) => MoveKind::Castle(castle),
+            (None, None) => MoveKind::Move,
+        }
+    }
+}
+
+impl From<PackedMove> for PseudoMove {
+    #[inline]
+    fn from(movement: PackedMove) -> Self {
+        PseudoMove {
+            origin: movement.origin(),
+            destination: movement.destination(),
+            kind: movement.kind(),
+        }
+    }
+}
+
+impl Display for PackedMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        PseudoMove::from(*self).fmt(f)
+    }
+}
+
+impl BorkedBoard {
+    /// Packs `movement`, which must be a move of the side to move, taking the
+    /// flags from the position.
+    #[must_use]
+    pub fn pack(&self, movement: &PseudoMove) -> PackedMove {
+        let PseudoMove {
+            origin,
+            destination,
+            ref kind,
+        } = *movement;
+
+        let theirs = self.side(self.turn.opposite());
+        let capture = theirs.occupancy.get(destination);
+        let is_pawn = self.side(self.turn).pieces.piece(Piece::Pawn).get(origin);
+
+        match *kind {
+            MoveKind::Castle(castle) => PackedMove::castle(origin, destination, castle),
+            MoveKind::Promote(piece) => PackedMove::promotion(origin, destination, piece, capture),
+            MoveKind::Move if capture => PackedMove::capture(origin, destination),
+            MoveKind::Move if is_pawn && theirs.en_passant == Some(destination) => {
+                PackedMove::en_passant(origin, destination)
+            }
+            MoveKind::Move
+                if is_pawn && origin.rank::<usize>().abs_diff(destination.rank()) == 2 =>
+            {
+                PackedMove::double_pawn_push(origin, destination)
+            }
+            MoveKind::Move => PackedMove::quiet(origin, destination),
+        }
+    }
+}
@@ -1,9 +1,54 @@
-use super::{MoveKind, PseudoMove};
-use crate::Square;
+use super::{LegalMove, MoveKind, PackedMove, PseudoMove, SANParseError};
+use crate::{BorkedBoard, Square};
 
-use std::convert::TryFrom;
+use std::convert::{Infallible, TryFrom};
 use std::str::FromStr;
 
+/// Anything that can be turned into a move for a given position, such as
+/// a [`PseudoMove`] or a string in coordinate or algebraic notation.
+pub trait TryIntoMove {
+    type Error;
+
+    fn try_into_move(self, board: &BorkedBoard) -> Result<PseudoMove, Self::Error>;
+}
+
+impl TryIntoMove for PseudoMove {
+    type Error = Infallible;
+
+    fn try_into_move(self, _board: &BorkedBoard) -> Result<PseudoMove, Self::Error> {
+        Ok(self)
+    }
+}
+
+impl TryIntoMove for LegalMove<'_> {
+    type Error = Infallible;
+
+    fn try_into_move(self, _board: &BorkedBoard) -> Result<PseudoMove, Self::Error> {
+        Ok(self.into())
+    }
+}
+
+impl TryIntoMove for PackedMove {
+    type Error = Infallible;
+
+    fn try_into_move(self, _board: &BorkedBoard) -> Result<PseudoMove, Self::Error> {
+        Ok(self.into())
+    }
+}
+
+impl TryIntoMove for &str {
+    type Error = SANParseError;
+
+    /// Tries coordinate notation (`e2e4`) first, and SAN (`Nf3`) otherwise.
+    fn try_into_move(self, board: &BorkedBoard) -> Result<PseudoMove, Self::Error> {
+        if let Ok(movement) = PseudoMove::from_str(self) {
+            return Ok(movement);
+        }
+
+        board.parse_san(self).map(PseudoMove::from)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum MoveParseError {
     TooShort,
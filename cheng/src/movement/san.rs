@@ -0,0 +1,156 @@
+use super::{Castle, LegalMove, MoveKind, PseudoMove};
+use crate::{pieces::Piece, square::Square, Board, BorkedBoard};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SANParseError {
+    Malformed,
+    AmbiguousMove,
+    IllegalMove,
+}
+
+/// The information written in a SAN move, before resolving it against a
+/// board.
+#[derive(Debug, PartialEq, Eq)]
+enum ParsedSAN {
+    Castle(Castle),
+    Move {
+        piece: Piece,
+        file: Option<usize>,
+        rank: Option<usize>,
+        destination: Square,
+        promotion: Option<Piece>,
+    },
+}
+
+impl ParsedSAN {
+    fn parse(san: &str) -> Result<Self, SANParseError> {
+        // Check, checkmate and annotation suffixes don't change the move.
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+
+        match san {
+            "O-O" | "0-0" => return Ok(Self::Castle(Castle::KingSide)),
+            "O-O-O" | "0-0-0" => return Ok(Self::Castle(Castle::QueenSide)),
+            _ => {}
+        }
+
+        if !san.is_ascii() {
+            return Err(SANParseError::Malformed);
+        }
+
+        let (san, promotion) = match san.split_once('=') {
+            Some((san, promotion)) => (san, Some(Self::parse_piece(promotion)?)),
+            None => (san, None),
+        };
+
+        let (piece, san) = match san.chars().next() {
+            Some(chr) if chr.is_ascii_uppercase() => (Self::parse_piece(&san[..1])?, &san[1..]),
+            Some(_) => (Piece::Pawn, san),
+            None => return Err(SANParseError::Malformed),
+        };
+
+        if san.len() < 2 || (piece != Piece::Pawn && promotion.is_some()) {
+            return Err(SANParseError::Malformed);
+        }
+
+        let (disambiguation, destination) = san.split_at(san.len() - 2);
+        let destination: Square = destination.parse().map_err(|_| SANParseError::Malformed)?;
+
+        let disambiguation = disambiguation.strip_suffix('x').unwrap_or(disambiguation);
+
+        let mut file = None;
+        let mut rank = None;
+        for chr in disambiguation.chars() {
+            match chr {
+                'a'..='h' if file.is_none() && rank.is_none() => {
+                    file = Some(chr as usize - 'a' as usize);
+                }
+                '1'..='8' if rank.is_none() => rank = Some(chr as usize - '1' as usize),
+                _ => return Err(SANParseError::Malformed),
+            }
+        }
+
+        Ok(Self::Move {
+            piece,
+            file,
+            rank,
+            destination,
+            promotion,
+        })
+    }
+
+    fn parse_piece(piece: &str) -> Result<Piece, SANParseError> {
+        let mut chars = piece.chars();
+        match (chars.next(), chars.next()) {
+            (Some(chr @ ('N' | 'B' | 'R' | 'Q' | 'K')), None) => chr
+                .to_ascii_lowercase()
+                .try_into()
+                .map_err(|()| SANParseError::Malformed),
+            _ => Err(SANParseError::Malformed),
+        }
+    }
+
+    fn matches(&self, board: &BorkedBoard, movement: &PseudoMove) -> bool {
+        match *self {
+            Self::Castle(castle) => movement.kind == MoveKind::Castle(castle),
+            Self::Move {
+                piece,
+                file,
+                rank,
+                destination,
+                promotion,
+            } => {
+                let moved_piece = board.side(board.turn).pieces.find(movement.origin);
+                let movement_promotion = match movement.kind {
+                    MoveKind::Promote(piece) => Some(piece),
+                    MoveKind::Move => None,
+                    MoveKind::Castle(_) => return false,
+                };
+
+                moved_piece == Some(piece)
+                    && movement.destination == destination
+                    && file.is_none_or(|file| movement.origin.file::<usize>() == file)
+                    && rank.is_none_or(|rank| movement.origin.rank::<usize>() == rank)
+                    && movement_promotion == promotion
+            }
+        }
+    }
+}
+
+impl BorkedBoard {
+    /// Parses a move in Standard Algebraic Notation, such as `Nf3`, `exd5`,
+    /// `Rad1`, `O-O` or `e8=Q+`, and resolves it against the legal moves of
+    /// the position.
+    pub fn parse_san(&self, san: &str) -> Result<LegalMove<'_>, SANParseError> {
+        let parsed = ParsedSAN::parse(san)?;
+
+        let mut scratch = self.clone();
+        let mut found = None;
+        for pseudomove in self.moves() {
+            if !parsed.matches(self, &pseudomove) {
+                continue;
+            }
+
+            let undo = scratch.make(&pseudomove);
+            let borked = scratch.is_borked();
+            scratch.unmake(undo);
+            if borked {
+                continue;
+            }
+
+            if found.is_some() {
+                return Err(SANParseError::AmbiguousMove);
+            }
+            found = Some(pseudomove);
+        }
+
+        let movement = found.ok_or(SANParseError::IllegalMove)?;
+        Ok(unsafe { LegalMove::unchecked_new(movement, self) })
+    }
+}
+
+impl Board {
+    /// See [`BorkedBoard::parse_san`].
+    pub fn parse_san(&self, san: &str) -> Result<LegalMove<'_>, SANParseError> {
+        self.inner().parse_san(san)
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    convert::Infallible,
+    ops::{AddAssign, ControlFlow},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use crate::{BoardMask, BorkedBoard, PackedMove};
+
+/// Counts the leaves of the move tree of a position, splitting the moves at
+/// the root across threads. Subtrees already counted are looked up in a table
+/// shared by all the threads, which is kept between runs.
+pub struct Perft {
+    threads: usize,
+    pub(crate) table: PerftTable,
+}
+
+impl Perft {
+    /// A perft driver with `threads` threads and a table of about
+    /// `table_size` bytes. A size of 0 disables the table.
+    #[must_use]
+    pub fn new(threads: usize, table_size: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            table: PerftTable::new(table_size),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Forgets the subtrees counted so far.
+    pub fn clear(&self) {
+        self.table.clear();
+    }
+
+    /// The number of leaves `depth` plies below `board`.
+    #[must_use]
+    pub fn perft(&self, board: &BorkedBoard, depth: usize) -> usize {
+        if depth == 0 {
+            return 1;
+        }
+
+        self.divide(board, depth)
+            .into_iter()
+            .map(|(_, nodes)| nodes)
+            .sum()
+    }
+
+    /// The number of leaves under each legal move of `board`, in the order
+    /// they are generated.
+    #[must_use]
+    pub fn divide(&self, board: &BorkedBoard, depth: usize) -> Vec<(PackedMove, usize)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        self.split(board, |board, _| self.count(board, depth - 1))
+    }
+
+    /// Like [`Perft::divide`], but calls `callback` with each move as soon as
+    /// its count is known, in the order they are generated. Once `callback`
+    /// breaks no more moves are counted.
+    pub fn divide_with<B>(
+        &self,
+        board: &BorkedBoard,
+        depth: usize,
+        callback: impl FnMut(PackedMove, usize) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        if depth == 0 {
+            return ControlFlow::Continue(());
+        }
+
+        self.split_with(board, |board, _| self.count(board, depth - 1), callback)
+    }
+
+    /// The leaves `depth` plies below `board`, by the kind of the last move.
+    /// See [`BorkedBoard::perft_stats`], this one splits the moves at the root
+    /// across threads.
+    #[must_use]
+    pub fn perft_stats(&self, board: &BorkedBoard, depth: usize) -> PerftStats {
+        if depth == 0 {
+            return board.clone().perft_stats(0);
+        }
+
+        let mut stats = PerftStats::default();
+        for (_, move_stats) in self.divide_stats(board, depth) {
+            stats += move_stats;
+        }
+        stats
+    }
+
+    /// Like [`Perft::divide`], with the stats of the leaves under each move
+    /// instead of just their number.
+    #[must_use]
+    pub fn divide_stats(&self, board: &BorkedBoard, depth: usize) -> Vec<(PackedMove, PerftStats)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        self.split(board, |board, movement| {
+            if depth == 1 {
+                PerftStats::leaf(movement, board)
+            } else {
+                board.perft_stats(depth - 1)
+            }
+        })
+    }
+
+    /// Calls `visit` after each legal move of `board`, spreading the moves
+    /// across the threads. The results are in the order the moves are
+    /// generated.
+    fn split<T, F>(&self, board: &BorkedBoard, visit: F) -> Vec<(PackedMove, T)>
+    where
+        T: Send,
+        F: Fn(&mut BorkedBoard, PackedMove) -> T + Sync,
+    {
+        let mut results = Vec::new();
+        let ControlFlow::Continue(()) = self.split_with(board, visit, |movement, result| {
+            results.push((movement, result));
+            ControlFlow::<Infallible>::Continue(())
+        });
+        results
+    }
+
+    /// Like [`Perft::split`], but hands each result to `on_result` as soon as
+    /// it and the ones of the moves generated before it are known. Once
+    /// `on_result` breaks, the threads finish the moves they are on and stop.
+    fn split_with<T, F, B>(
+        &self,
+        board: &BorkedBoard,
+        visit: F,
+        mut on_result: impl FnMut(PackedMove, T) -> ControlFlow<B>,
+    ) -> ControlFlow<B>
+    where
+        T: Send,
+        F: Fn(&mut BorkedBoard, PackedMove) -> T + Sync,
+    {
+        let moves = board.legal_moves();
+        let mut results: Vec<Option<T>> = moves.iter().map(|_| None).collect();
+        let next = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+
+        // Each thread takes the next move until there are none left, sending
+        // back the result of each one.
+        thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            for _ in 0..self.threads.min(moves.len()) {
+                let sender = sender.clone();
+                let (moves, next, stop, visit) = (&moves, &next, &stop, &visit);
+                scope.spawn(move || {
+                    let mut board = board.clone();
+                    while !stop.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&movement) = moves.get(index) else {
+                            break;
+                        };
+
+                        let undo = board.make_packed(movement);
+                        let result = visit(&mut board, movement);
+                        board.unmake(undo);
+
+                        if sender.send((index, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            let mut reported = 0;
+            for (index, result) in receiver {
+                results[index] = Some(result);
+
+                while let Some(result) = results.get_mut(reported).and_then(Option::take) {
+                    let control = on_result(moves[reported], result);
+                    reported += 1;
+
+                    if control.is_break() {
+                        stop.store(true, Ordering::Relaxed);
+                        return control;
+                    }
+                }
+            }
+
+            ControlFlow::Continue(())
+        })
+    }
+
+    fn count(&self, board: &mut BorkedBoard, depth: usize) -> usize {
+        if depth == 0 {
+            return 1;
+        }
+
+        let hash = board.hash();
+        if depth > 1 {
+            if let Some(nodes) = self.table.probe(hash, depth) {
+                return nodes;
+            }
+        }
+
+        let moves = board.legal_moves();
+        // Bulk counting: the leaves are the moves themselves.
+        if depth == 1 {
+            return moves.len();
+        }
+
+        let mut nodes = 0;
+        for movement in moves {
+            let undo = board.make_packed(movement);
+            nodes += self.count(board, depth - 1);
+            board.unmake(undo);
+        }
+
+        self.table.store(hash, depth, nodes);
+        nodes
+    }
+}
+
+impl Default for Perft {
+    /// As many threads as the machine can run in parallel and a 16 MiB table.
+    fn default() -> Self {
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+        Self::new(threads, 16 << 20)
+    }
+}
+
+/// The leaves of a perft by the kind of the move that reaches them, like the
+/// tables of the chessprogramming wiki. Checks are counted once, however many
+/// pieces give them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PerftStats {
+    pub nodes: usize,
+    /// Captures, en passant included.
+    pub captures: usize,
+    pub en_passant: usize,
+    pub castles: usize,
+    pub promotions: usize,
+    pub checks: usize,
+    /// Checks given by a single piece other than the one that moved. Double
+    /// checks aren't included.
+    pub discovered_checks: usize,
+    pub double_checks: usize,
+    pub checkmates: usize,
+}
+
+impl PerftStats {
+    /// The stats of the single leaf reached by `movement`, with `board` the
+    /// position after it.
+    fn leaf(movement: PackedMove, board: &BorkedBoard) -> Self {
+        let moved = match movement.castle_kind() {
+            // The king can't give check, only the rook.
+            Some(castle) => castle.rook_square_after_castle(board.turn.opposite()),
+            None => movement.destination(),
+        };
+        let checkers = board.checkers();
+        let check = checkers != BoardMask::default();
+        let double_check = checkers.count() > 1;
+
+        Self {
+            nodes: 1,
+            captures: usize::from(movement.is_capture()),
+            en_passant: usize::from(movement.is_en_passant()),
+            castles: usize::from(movement.castle_kind().is_some()),
+            promotions: usize::from(movement.promotion_piece().is_some()),
+            checks: usize::from(check),
+            discovered_checks: usize::from(
+                !double_check && checkers.without(BoardMask::from(moved)) != BoardMask::default(),
+            ),
+            double_checks: usize::from(double_check),
+            checkmates: usize::from(check && board.legal_moves().is_empty()),
+        }
+    }
+}
+
+impl AddAssign for PerftStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.nodes += rhs.nodes;
+        self.captures += rhs.captures;
+        self.en_passant += rhs.en_passant;
+        self.castles += rhs.castles;
+        self.promotions += rhs.promotions;
+        self.checks += rhs.checks;
+        self.discovered_checks += rhs.discovered_checks;
+        self.double_checks += rhs.double_checks;
+        self.checkmates += rhs.checkmates;
+    }
+}
+
+impl BorkedBoard {
+    /// Like [`BorkedBoard::perft`], but also counts the leaves by the kind of
+    /// the move that reaches them.
+    pub fn perft_stats(&mut self, depth: usize) -> PerftStats {
+        let mut stats = PerftStats::default();
+        if depth == 0 {
+            stats.nodes = 1;
+            return stats;
+        }
+
+        for movement in self.legal_moves() {
+            let undo = self.make_packed(movement);
+            if depth == 1 {
+                stats += PerftStats::leaf(movement, self);
+            } else {
+                stats += self.perft_stats(depth - 1);
+            }
+            self.unmake(undo);
+        }
+
+        stats
+    }
+}
+
+/// Node counts indexed by position and depth. Entries are written without
+/// locks: the key is stored xored with the data, so an entry torn by two
+/// threads writing at once doesn't match any position.
+pub(crate) struct PerftTable {
+    entries: Box<[PerftEntry]>,
+}
+
+#[derive(Default)]
+struct PerftEntry {
+    key: AtomicU64,
+    /// The node count in the upper bits and the depth in the lowest byte.
+    data: AtomicU64,
+}
+
+impl PerftTable {
+    fn new(size: usize) -> Self {
+        let len = size / std::mem::size_of::<PerftEntry>();
+        // A power of two, so the index is a mask of the hash.
+        let len = if len == 0 { 0 } else { 1 << len.ilog2() };
+
+        Self {
+            entries: (0..len).map(|_| PerftEntry::default()).collect(),
+        }
+    }
+
+    fn clear(&self) {
+        for entry in self.entries.iter() {
+            entry.key.store(0, Ordering::Relaxed);
+            entry.data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    fn entry(&self, hash: u64) -> Option<&PerftEntry> {
+        let mask = self.entries.len().checked_sub(1)?;
+        Some(&self.entries[hash as usize & mask])
+    }
+
+    pub(crate) fn probe(&self, hash: u64, depth: usize) -> Option<usize> {
+        let entry = self.entry(hash)?;
+        let data = entry.data.load(Ordering::Relaxed);
+        let key = entry.key.load(Ordering::Relaxed);
+
+        let hit = key ^ data == hash && data & 0xff == depth as u64;
+        hit.then_some((data >> 8) as usize)
+    }
+
+    fn store(&self, hash: u64, depth: usize, nodes: usize) {
+        let Some(entry) = self.entry(hash) else {
+            return;
+        };
+
+        // Depths never get near 256, and counts near 2^56 would take years.
+        let data = (nodes as u64) << 8 | depth as u64;
+        entry.key.store(hash ^ data, Ordering::Relaxed);
+        entry.data.store(data, Ordering::Relaxed);
+    }
+}
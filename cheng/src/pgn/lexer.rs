@@ -0,0 +1,259 @@
+use super::{PgnError, PgnErrorKind, PgnResult};
+
+use std::io::BufRead;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    OpenVariation,
+    CloseVariation,
+    MoveNumber,
+    Result(PgnResult),
+    San(String),
+}
+
+/// A line and a column, both starting at 1.
+pub type Position = (usize, usize);
+
+/// Splits PGN text into tokens, reading the input one line at a time.
+pub struct Lexer<R> {
+    reader: R,
+    // The current line, including the trailing newline.
+    line: Vec<char>,
+    line_number: usize,
+    column: usize,
+}
+
+impl<R: BufRead> Lexer<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::new(),
+            line_number: 0,
+            column: 0,
+        }
+    }
+
+    /// The line and column (both starting at 1) of the next character.
+    pub fn position(&self) -> Position {
+        (self.line_number, self.column + 1)
+    }
+
+    pub fn error(&self, kind: PgnErrorKind) -> PgnError {
+        let (line, column) = self.position();
+        PgnError { line, column, kind }
+    }
+
+    fn read_line(&mut self) -> Result<bool, PgnError> {
+        loop {
+            let mut line = String::new();
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .map_err(|err| self.error(PgnErrorKind::Io(err.kind())))?;
+
+            if read == 0 {
+                self.line.clear();
+                self.column = 0;
+                return Ok(false);
+            }
+
+            self.line_number += 1;
+            self.column = 0;
+
+            // Lines starting with `%` are escaped and must be ignored.
+            if line.starts_with('%') {
+                continue;
+            }
+
+            self.line = line.trim_end_matches(['\n', '\r']).chars().collect();
+            self.line.push('\n');
+            return Ok(true);
+        }
+    }
+
+    fn peek_char(&mut self) -> Result<Option<char>, PgnError> {
+        while self.column >= self.line.len() {
+            if !self.read_line()? {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(self.line[self.column]))
+    }
+
+    fn bump(&mut self) {
+        self.column += 1;
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> Result<String, PgnError> {
+        let mut taken = String::new();
+        while let Some(chr) = self.peek_char()? {
+            if !predicate(chr) {
+                break;
+            }
+
+            taken.push(chr);
+            self.bump();
+        }
+        Ok(taken)
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), PgnError> {
+        self.take_while(char::is_whitespace).map(|_| ())
+    }
+
+    /// Discards input until something that looks like the tags of the next
+    /// game: a line starting with `[` after an empty line.
+    pub fn skip_game(&mut self) -> Result<(), PgnError> {
+        let mut previous_is_blank = false;
+        self.column = self.line.len();
+
+        while self.read_line()? {
+            if previous_is_blank && self.line[0] == '[' {
+                return Ok(());
+            }
+
+            previous_is_blank = self.line.iter().all(|chr| chr.is_whitespace());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the next token and the line and column where it starts.
+    pub fn next_token(&mut self) -> Result<Option<(Token, Position)>, PgnError> {
+        self.skip_whitespace()?;
+
+        let position = self.position();
+        let Some(chr) = self.peek_char()? else {
+            return Ok(None);
+        };
+
+        let token = match chr {
+            '[' => {
+                self.bump();
+                self.lex_tag(position)?
+            }
+            '{' => {
+                self.bump();
+                let comment = self.take_while(|chr| chr != '}')?;
+                if self.peek_char()?.is_none() {
+                    return Err(PgnError {
+                        line: position.0,
+                        column: position.1,
+                        kind: PgnErrorKind::UnterminatedComment,
+                    });
+                }
+                self.bump();
+                // Comments may span several lines, but line breaks are just
+                // whitespace.
+                let words: Vec<_> = comment.split_whitespace().collect();
+                Token::Comment(words.join(" "))
+            }
+            ';' => {
+                self.bump();
+                let comment = self.take_while(|chr| chr != '\n')?;
+                Token::Comment(comment.trim().to_string())
+            }
+            '(' => {
+                self.bump();
+                Token::OpenVariation
+            }
+            ')' => {
+                self.bump();
+                Token::CloseVariation
+            }
+            '*' => {
+                self.bump();
+                Token::Result(PgnResult::Unknown)
+            }
+            '$' => {
+                self.bump();
+                let nag = self.take_while(|chr| chr.is_ascii_digit())?;
+                Token::Nag(
+                    nag.parse()
+                        .map_err(|_| self.error(PgnErrorKind::UnexpectedCharacter))?,
+                )
+            }
+            '!' | '?' => {
+                let suffix = self.take_while(|chr| chr == '!' || chr == '?')?;
+                let nag = match suffix.as_str() {
+                    "!" => 1,
+                    "?" => 2,
+                    "!!" => 3,
+                    "??" => 4,
+                    "!?" => 5,
+                    "?!" => 6,
+                    _ => {
+                        return Err(PgnError {
+                            line: position.0,
+                            column: position.1,
+                            kind: PgnErrorKind::UnexpectedCharacter,
+                        })
+                    }
+                };
+                Token::Nag(nag)
+            }
+            chr if chr.is_ascii_alphanumeric() => {
+                let symbol =
+                    self.take_while(|chr| chr.is_ascii_alphanumeric() || "_+#=:-/".contains(chr))?;
+
+                if let Ok(result) = symbol.parse() {
+                    Token::Result(result)
+                } else if symbol.chars().all(|chr| chr.is_ascii_digit()) {
+                    self.take_while(|chr| chr == '.')?;
+                    Token::MoveNumber
+                } else {
+                    Token::San(symbol)
+                }
+            }
+            _ => return Err(self.error(PgnErrorKind::UnexpectedCharacter)),
+        };
+
+        Ok(Some((token, position)))
+    }
+
+    fn lex_tag(&mut self, position: Position) -> Result<Token, PgnError> {
+        let malformed = PgnError {
+            line: position.0,
+            column: position.1,
+            kind: PgnErrorKind::MalformedTag,
+        };
+
+        self.skip_whitespace()?;
+        let name = self.take_while(|chr| chr.is_ascii_alphanumeric() || chr == '_')?;
+        self.skip_whitespace()?;
+        if name.is_empty() || self.peek_char()? != Some('"') {
+            return Err(malformed);
+        }
+        self.bump();
+
+        let mut value = String::new();
+        loop {
+            match self.peek_char()? {
+                Some('"') => break,
+                Some('\\') => {
+                    self.bump();
+                    match self.peek_char()? {
+                        Some(chr @ ('"' | '\\')) => value.push(chr),
+                        _ => return Err(malformed),
+                    }
+                }
+                Some('\n') | None => return Err(malformed),
+                Some(chr) => value.push(chr),
+            }
+            self.bump();
+        }
+        self.bump();
+
+        self.skip_whitespace()?;
+        if self.peek_char()? != Some(']') {
+            return Err(malformed);
+        }
+        self.bump();
+
+        Ok(Token::Tag(name, value))
+    }
+}
@@ -0,0 +1,192 @@
+mod lexer;
+
+mod reader;
+pub use reader::PgnReader;
+
+mod writer;
+pub use writer::PgnWriteOptions;
+
+use crate::{board::TryFeedError, Board, GameResult, PseudoMove, Side, TryIntoMove, SAN};
+
+use std::{fmt::Display, str::FromStr};
+
+/// The tags every PGN game must have, in the order they are exported.
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnGame {
+    /// Tags in the order they were read.
+    pub tags: Vec<(String, String)>,
+    /// The position the game starts from, taken from the `FEN` tag if any.
+    pub initial_board: Board,
+    pub moves: Vec<PgnMove>,
+    /// Comments of games without any move.
+    pub comments: Vec<String>,
+    pub result: PgnResult,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnMove {
+    pub movement: PseudoMove,
+    /// The move as it was written.
+    pub san: String,
+    pub nags: Vec<u8>,
+    /// Comments written before the move, only possible at the start of a line.
+    pub comments_before: Vec<String>,
+    pub comments: Vec<String>,
+    /// Alternatives to this move, starting from the position before it.
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PgnResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PgnError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: PgnErrorKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PgnErrorKind {
+    Io(std::io::ErrorKind),
+    UnexpectedCharacter,
+    UnexpectedEof,
+    UnexpectedToken,
+    UnterminatedComment,
+    MalformedTag,
+    InvalidFen,
+    IllegalMove(crate::SANParseError),
+    UnmatchedParenthesis,
+}
+
+impl PgnGame {
+    /// A game without tags nor moves, starting from `initial_board`.
+    #[must_use]
+    pub fn new(initial_board: Board) -> Self {
+        Self {
+            tags: Vec::new(),
+            result: initial_board.result().into(),
+            initial_board,
+            moves: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// The position at the end of the main line.
+    #[must_use]
+    pub fn board(&self) -> Board {
+        let mut board = self.initial_board.clone();
+        for pgn_move in &self.moves {
+            board
+                .try_feed(pgn_move.movement.clone())
+                .expect("moves of the main line must be legal");
+        }
+        board
+    }
+
+    /// Plays `movement` at the end of the main line, updating the result.
+    pub fn push<M>(&mut self, movement: M) -> Result<(), TryFeedError<M::Error>>
+    where
+        M: TryIntoMove,
+    {
+        let mut board = self.board();
+        let movement = movement
+            .try_into_move(board.inner())
+            .map_err(TryFeedError::Parsing)?;
+        let Some(legal_move) = board.validate(movement) else {
+            return Err(TryFeedError::InvalidMove);
+        };
+
+        let san = SAN(&legal_move, &board).to_string();
+        let movement = PseudoMove::from(legal_move);
+        board
+            .try_feed(movement.clone())
+            .map_err(|_| TryFeedError::InvalidMove)?;
+
+        self.moves.push(PgnMove::new(movement, san));
+        self.result = board.result().into();
+        Ok(())
+    }
+}
+
+impl Display for PgnGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_pgn(&PgnWriteOptions::default()))
+    }
+}
+
+impl PgnMove {
+    pub(crate) fn new(movement: PseudoMove, san: String) -> Self {
+        Self {
+            movement,
+            san,
+            nags: Vec::new(),
+            comments_before: Vec::new(),
+            comments: Vec::new(),
+            variations: Vec::new(),
+        }
+    }
+}
+
+impl From<GameResult> for PgnResult {
+    fn from(result: GameResult) -> Self {
+        match result {
+            GameResult::Undecided => Self::Unknown,
+            GameResult::Draw { .. } => Self::Draw,
+            GameResult::Checkmate {
+                winner: Side::White,
+            } => Self::WhiteWins,
+            GameResult::Checkmate {
+                winner: Side::Black,
+            } => Self::BlackWins,
+        }
+    }
+}
+
+impl FromStr for PgnResult {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "1-0" => Self::WhiteWins,
+            "0-1" => Self::BlackWins,
+            "1/2-1/2" => Self::Draw,
+            "*" => Self::Unknown,
+            _ => return Err(()),
+        })
+    }
+}
+
+impl Display for PgnResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::WhiteWins => "1-0",
+            Self::BlackWins => "0-1",
+            Self::Draw => "1/2-1/2",
+            Self::Unknown => "*",
+        })
+    }
+}
@@ -0,0 +1,229 @@
+use super::{
+    lexer::{Lexer, Position, Token},
+    PgnError, PgnErrorKind, PgnGame, PgnMove, PgnResult,
+};
+use crate::{Board, BorkedBoard, FromIntoFen, PseudoMove, SANParseError};
+
+use std::{io::BufRead, str::FromStr};
+
+/// Reads the games of a PGN file one by one.
+///
+/// After an error the reader skips to the next game, so the remaining games
+/// of the file can still be read. A game missing its result ends at the first
+/// tag at the start of a line, with an unknown result.
+pub struct PgnReader<R> {
+    lexer: Lexer<R>,
+    // A token read past the end of a game, which starts the next one.
+    pending: Option<(Token, Position)>,
+    failed: bool,
+    finished: bool,
+}
+
+struct Line {
+    moves: Vec<PgnMove>,
+    // Comments that couldn't be attached to any move.
+    comments: Vec<String>,
+    result: Option<PgnResult>,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lexer: Lexer::new(reader),
+            pending: None,
+            failed: false,
+            finished: false,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<(Token, Position), PgnError> {
+        self.lexer
+            .next_token()?
+            .ok_or_else(|| self.lexer.error(PgnErrorKind::UnexpectedEof))
+    }
+
+    fn read_game(&mut self) -> Result<Option<PgnGame>, PgnError> {
+        let mut tags = Vec::new();
+        let mut first_token = None;
+        while let Some((token, position)) = match self.pending.take() {
+            Some(token) => Some(token),
+            None => self.lexer.next_token()?,
+        } {
+            match token {
+                Token::Tag(name, value) => tags.push((name, value)),
+                token => {
+                    first_token = Some((token, position));
+                    break;
+                }
+            }
+        }
+
+        if tags.is_empty() && first_token.is_none() {
+            return Ok(None);
+        }
+
+        let fen = tags
+            .iter()
+            .find(|(name, _)| name == "FEN")
+            .map(|(_, value)| value.as_str());
+
+        let initial_board = match fen {
+            Some(fen) => BorkedBoard::from_fen(fen)
+                .ok()
+                .and_then(|board| Board::try_from(board).ok())
+                .ok_or_else(|| self.lexer.error(PgnErrorKind::InvalidFen))?,
+            None => Board::default(),
+        };
+
+        let line = self.read_line(initial_board.clone(), first_token, false)?;
+        Ok(Some(PgnGame {
+            tags,
+            initial_board,
+            moves: line.moves,
+            comments: line.comments,
+            result: line.result.unwrap(),
+        }))
+    }
+
+    fn read_line(
+        &mut self,
+        mut board: Board,
+        mut first_token: Option<(Token, Position)>,
+        is_variation: bool,
+    ) -> Result<Line, PgnError> {
+        let mut moves: Vec<PgnMove> = Vec::new();
+        let mut comments = Vec::new();
+        let mut board_before_last_move = None;
+
+        loop {
+            let (token, (line, column)) = match first_token.take() {
+                Some(token) => token,
+                None => self.next_token()?,
+            };
+            let error = |kind| PgnError { line, column, kind };
+
+            match token {
+                Token::MoveNumber => {}
+                Token::San(san) => {
+                    let movement = PseudoMove::from(
+                        board
+                            .parse_san(&san)
+                            .map_err(|err| error(PgnErrorKind::IllegalMove(err)))?,
+                    );
+
+                    let before = board.clone();
+                    board.try_feed(movement.clone()).map_err(|_| {
+                        error(PgnErrorKind::IllegalMove(SANParseError::IllegalMove))
+                    })?;
+                    board_before_last_move = Some(before);
+
+                    let mut pgn_move = PgnMove::new(movement, san);
+                    pgn_move.comments_before = std::mem::take(&mut comments);
+                    moves.push(pgn_move);
+                }
+                Token::Comment(comment) => match moves.last_mut() {
+                    Some(last) => last.comments.push(comment),
+                    None => comments.push(comment),
+                },
+                Token::Nag(nag) => match moves.last_mut() {
+                    Some(last) => last.nags.push(nag),
+                    None => return Err(error(PgnErrorKind::UnexpectedToken)),
+                },
+                Token::OpenVariation => {
+                    let Some(before) = board_before_last_move.clone() else {
+                        return Err(error(PgnErrorKind::UnexpectedToken));
+                    };
+
+                    let variation = self.read_line(before, None, true)?;
+                    let last = moves.last_mut().unwrap();
+                    if variation.moves.is_empty() {
+                        last.comments.extend(variation.comments);
+                    } else {
+                        last.variations.push(variation.moves);
+                    }
+                }
+                Token::CloseVariation if is_variation => {
+                    return Ok(Line {
+                        moves,
+                        comments,
+                        result: None,
+                    });
+                }
+                Token::CloseVariation => {
+                    return Err(error(PgnErrorKind::UnmatchedParenthesis));
+                }
+                // The variation was never closed.
+                Token::Result(_) if is_variation => {
+                    return Err(error(PgnErrorKind::UnmatchedParenthesis));
+                }
+                Token::Result(result) => {
+                    return Ok(Line {
+                        moves,
+                        comments,
+                        result: Some(result),
+                    });
+                }
+                // A tag at the start of a line is the next game, this one ended
+                // without a result.
+                Token::Tag(..) if column == 1 => {
+                    self.pending = Some((token, (line, column)));
+                    if is_variation {
+                        return Err(error(PgnErrorKind::UnmatchedParenthesis));
+                    }
+
+                    return Ok(Line {
+                        moves,
+                        comments,
+                        result: Some(PgnResult::Unknown),
+                    });
+                }
+                Token::Tag(..) => return Err(error(PgnErrorKind::UnexpectedToken)),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        // There is nothing to skip if the next game has already been found.
+        let skip = std::mem::take(&mut self.failed) && self.pending.is_none();
+        let game = if skip {
+            self.lexer.skip_game().and_then(|()| self.read_game())
+        } else {
+            self.read_game()
+        };
+
+        match game {
+            Ok(game) => game.map(Ok),
+            Err(err) => {
+                // I/O errors can't be recovered from, the input is over.
+                if matches!(err.kind, PgnErrorKind::Io(_)) {
+                    self.finished = true;
+                } else {
+                    self.failed = true;
+                }
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl FromStr for PgnGame {
+    type Err = PgnError;
+
+    /// Parses the first game of `s`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut reader = PgnReader::new(s.as_bytes());
+        reader.next().unwrap_or(Err(PgnError {
+            line: 1,
+            column: 1,
+            kind: PgnErrorKind::UnexpectedEof,
+        }))
+    }
+}
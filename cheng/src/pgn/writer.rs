@@ -0,0 +1,210 @@
+use super::{PgnGame, PgnMove, SEVEN_TAG_ROSTER};
+use crate::{Board, FromIntoFen, Side, SAN};
+
+use std::fmt::Write;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnWriteOptions {
+    /// Movetext lines are wrapped before exceeding this many characters.
+    pub line_width: usize,
+    pub comments: bool,
+    pub nags: bool,
+    pub variations: bool,
+}
+
+impl Default for PgnWriteOptions {
+    fn default() -> Self {
+        Self {
+            line_width: 80,
+            comments: true,
+            nags: true,
+            variations: true,
+        }
+    }
+}
+
+/// Builds the movetext as a list of tokens that can't be split by the line
+/// wrapping.
+struct Movetext<'a> {
+    options: &'a PgnWriteOptions,
+    tokens: Vec<String>,
+    // Prepended to the next token, used to open variations.
+    prefix: String,
+}
+
+impl Movetext<'_> {
+    fn push(&mut self, token: String) {
+        self.tokens.push(std::mem::take(&mut self.prefix) + &token);
+    }
+
+    fn push_comment(&mut self, comment: &str) {
+        // A comment can't be escaped, so a `}` would end it early.
+        let comment = comment.replace('}', "");
+        let words: Vec<_> = comment.split_whitespace().collect();
+        match words.as_slice() {
+            [] => self.push("{}".to_string()),
+            [word] => self.push(format!("{{{word}}}")),
+            [first, middle @ .., last] => {
+                self.push(format!("{{{first}"));
+                for word in middle {
+                    self.push((*word).to_string());
+                }
+                self.push(format!("{last}}}"));
+            }
+        }
+    }
+
+    /// Writes `moves`, played from `board`. The SAN is written again from the
+    /// moves, so it's canonical however it was read. Moves built by hand may
+    /// not be legal: from then on, `board` is `None` and the SAN is written as
+    /// it is.
+    fn push_line(
+        &mut self,
+        moves: &[PgnMove],
+        mut board: Option<Board>,
+        mut turn: Side,
+        mut fullmove: usize,
+    ) {
+        let mut needs_move_number = true;
+
+        for pgn_move in moves {
+            let before = board.clone();
+            let legal_move = board
+                .as_ref()
+                .and_then(|board| board.validate(pgn_move.movement.clone()));
+            let san = match (legal_move, board.as_mut()) {
+                (Some(legal_move), Some(board)) => {
+                    let san = SAN(&legal_move, board).to_string();
+                    board.feed(legal_move);
+                    san
+                }
+                _ => {
+                    board = None;
+                    pgn_move.san.clone()
+                }
+            };
+
+            if self.options.comments && !pgn_move.comments_before.is_empty() {
+                for comment in &pgn_move.comments_before {
+                    self.push_comment(comment);
+                }
+                needs_move_number = true;
+            }
+
+            match turn {
+                Side::White => self.push(format!("{fullmove}.")),
+                Side::Black if needs_move_number => self.push(format!("{fullmove}...")),
+                Side::Black => {}
+            }
+            self.push(san);
+            needs_move_number = false;
+
+            if self.options.nags {
+                for nag in &pgn_move.nags {
+                    self.push(format!("${nag}"));
+                }
+            }
+
+            if self.options.comments {
+                for comment in &pgn_move.comments {
+                    self.push_comment(comment);
+                    needs_move_number = true;
+                }
+            }
+
+            if self.options.variations {
+                for variation in &pgn_move.variations {
+                    self.prefix.push('(');
+                    self.push_line(variation, before.clone(), turn, fullmove);
+                    self.tokens.last_mut().unwrap().push(')');
+                    needs_move_number = true;
+                }
+            }
+
+            if turn == Side::Black {
+                fullmove += 1;
+            }
+            turn = turn.opposite();
+        }
+    }
+
+    fn wrap(&self, pgn: &mut String) {
+        let mut line_length = 0;
+        for token in &self.tokens {
+            if line_length > 0 && line_length + 1 + token.len() > self.options.line_width {
+                pgn.push('\n');
+                line_length = 0;
+            } else if line_length > 0 {
+                pgn.push(' ');
+                line_length += 1;
+            }
+
+            pgn.push_str(token);
+            line_length += token.len();
+        }
+        pgn.push('\n');
+    }
+}
+
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl PgnGame {
+    /// Exports the game as PGN. The seven tag roster is always written, using
+    /// `?` for missing tags, followed by `SetUp` and `FEN` for games that
+    /// don't start from the default position.
+    #[must_use]
+    pub fn to_pgn(&self, options: &PgnWriteOptions) -> String {
+        let mut pgn = String::new();
+
+        let result = self.result.to_string();
+        for name in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => result.as_str(),
+                "Date" => self.tag(name).unwrap_or("????.??.??"),
+                _ => self.tag(name).unwrap_or("?"),
+            };
+            writeln!(pgn, "[{name} \"{}\"]", escape_tag_value(value)).unwrap();
+        }
+
+        let fen = self.initial_board.as_fen();
+        if fen != Board::DEFAULT_FEN {
+            writeln!(pgn, "[SetUp \"1\"]").unwrap();
+            writeln!(pgn, "[FEN \"{fen}\"]").unwrap();
+        }
+
+        for (name, value) in &self.tags {
+            let is_written =
+                SEVEN_TAG_ROSTER.contains(&name.as_str()) || name == "SetUp" || name == "FEN";
+            if !is_written {
+                writeln!(pgn, "[{name} \"{}\"]", escape_tag_value(value)).unwrap();
+            }
+        }
+        pgn.push('\n');
+
+        let mut movetext = Movetext {
+            options,
+            tokens: Vec::new(),
+            prefix: String::new(),
+        };
+
+        if options.comments {
+            for comment in &self.comments {
+                movetext.push_comment(comment);
+            }
+        }
+
+        let inner = self.initial_board.inner();
+        movetext.push_line(
+            &self.moves,
+            Some(self.initial_board.clone()),
+            inner.turn,
+            inner.fullmove_clock,
+        );
+        movetext.push(result);
+        movetext.wrap(&mut pgn);
+
+        pgn
+    }
+}
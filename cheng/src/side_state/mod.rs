@@ -7,7 +7,7 @@ use crate::{
     board::BoardMask,
     movegen,
     movement::{Castle, MoveKind},
-    Piece, PseudoMove, Side, SidedPiece, Square,
+    zobrist, Piece, PseudoMove, Side, SidedPiece, Square,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -20,6 +20,11 @@ pub struct SideState {
     pub en_passant: Option<Square>,
     pub king_in_check: bool,
     pub castling_rights: CastlingRights,
+    /// Squares of the rooks castling is done with, indexed by [`Castle`].
+    /// They only differ from the corners in Chess960.
+    pub castling_rooks: [Square; 2],
+    /// Zobrist key of this side's pieces, castling rights and en passant square.
+    pub hash: u64,
 }
 
 impl SideState {
@@ -34,12 +39,41 @@ impl SideState {
             en_passant: None,
             king_in_check: false,
             castling_rights: CastlingRights::None,
+            castling_rooks: [
+                Castle::KingSide.standard_rook_square(side),
+                Castle::QueenSide.standard_rook_square(side),
+            ],
+            hash: 0,
         }
     }
 
+    #[inline]
+    #[must_use]
+    pub fn castling_rook_square(&self, castle: Castle) -> Square {
+        self.castling_rooks[castle as usize]
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn king_square(&self) -> Option<Square> {
+        self.pieces.piece(Piece::King).first()
+    }
+
+    #[must_use]
+    pub fn compute_hash(&self) -> u64 {
+        let mut hash = zobrist::castling_rights(self.side, self.castling_rights);
+
+        for (piece, square) in &self.pieces {
+            hash ^= zobrist::piece(self.side, piece, square);
+        }
+
+        hash
+    }
+
     pub fn put(&mut self, square: Square, piece: Piece) {
         self.occupancy.set(square);
         self.pieces.piece_mut(piece).set(square);
+        self.hash ^= zobrist::piece(self.side, piece, square);
 
         // NOTE: Here we don't update threaten pieces.
     }
@@ -56,18 +90,65 @@ impl SideState {
 
         self.pieces.piece_mut(piece).reset(square);
         self.occupancy.reset(square);
+        self.hash ^= zobrist::piece(self.side, piece, square);
 
         if piece != Piece::Rook {
             return;
         }
 
-        if square == Castle::KingSide.rook_square_before_castle(self.side) {
-            self.castling_rights = self.castling_rights.without(CastlingRights::KingSide);
-        } else if square == Castle::QueenSide.rook_square_before_castle(self.side) {
-            self.castling_rights = self.castling_rights.without(CastlingRights::QueenSide);
+        if square == self.castling_rook_square(Castle::KingSide) {
+            self.set_castling_rights(self.castling_rights.without(CastlingRights::KingSide));
+        } else if square == self.castling_rook_square(Castle::QueenSide) {
+            self.set_castling_rights(self.castling_rights.without(CastlingRights::QueenSide));
         }
     }
 
+    pub fn set_castling_rights(&mut self, castling_rights: CastlingRights) {
+        self.hash ^= zobrist::castling_rights(self.side, self.castling_rights);
+        self.hash ^= zobrist::castling_rights(self.side, castling_rights);
+        self.castling_rights = castling_rights;
+    }
+
+    /// The en passant square isn't part of the hash of the side: it only
+    /// matters when the other side can capture, see [`BorkedBoard::hash`].
+    ///
+    /// [`BorkedBoard::hash`]: crate::BorkedBoard::hash
+    pub fn set_en_passant(&mut self, en_passant: Option<Square>) {
+        self.en_passant = en_passant;
+    }
+
+    fn update_hash(&mut self, movement: &PseudoMove) {
+        let PseudoMove {
+            origin,
+            destination,
+            ref kind,
+        } = *movement;
+
+        match kind {
+            MoveKind::Move => {
+                let piece = self.pieces.find(origin).unwrap();
+                self.hash ^= zobrist::piece(self.side, piece, origin);
+                self.hash ^= zobrist::piece(self.side, piece, destination);
+            }
+            MoveKind::Promote(piece) => {
+                self.hash ^= zobrist::piece(self.side, Piece::Pawn, origin);
+                self.hash ^= zobrist::piece(self.side, *piece, destination);
+            }
+            MoveKind::Castle(_) => unreachable!("castles are hashed by `SideState::castle`"),
+        }
+    }
+
+    /// Moves the king and the castling rook to their squares after castling.
+    /// In Chess960 they may land on each other's squares (or stay where they
+    /// are), so both are lifted before being put back.
+    fn castle(&mut self, king: Square, castle: Castle) {
+        let rook = self.castling_rook_square(castle);
+        self.remove(king);
+        self.remove(rook);
+        self.put(castle.king_square_after_castle(self.side), Piece::King);
+        self.put(castle.rook_square_after_castle(self.side), Piece::Rook);
+    }
+
     fn is_two_square_pawn_move(&self, movement: &PseudoMove) -> bool {
         self.pieces.piece(Piece::Pawn).get(movement.origin)
             && (movement.destination.rank::<i32>() - movement.origin.rank::<i32>()).abs() == 2
@@ -82,29 +163,76 @@ impl SideState {
         } = movement;
 
         assert!(self.occupancy.get(*origin));
+
+        if let MoveKind::Castle(castle) = movement.kind {
+            self.set_en_passant(None);
+            self.set_castling_rights(CastlingRights::None);
+            self.castle(*origin, castle);
+            return;
+        }
+
         assert!(!self.occupancy.get(*destination));
 
         if self.is_two_square_pawn_move(&movement) {
-            self.en_passant = Some(origin.next_rank(self.side));
+            self.set_en_passant(Some(origin.next_rank(self.side)));
         } else {
-            self.en_passant = None;
+            self.set_en_passant(None);
         }
 
         self.occupancy.reset(*origin);
         self.occupancy.set(*destination);
 
-        if let MoveKind::Castle(castle) = movement.kind {
-            self.castling_rights = CastlingRights::None;
-
-            self.occupancy
-                .reset(castle.rook_square_before_castle(self.side));
-            self.occupancy
-                .set(castle.rook_square_after_castle(self.side));
-        } else if let MoveKind::Move = movement.kind {
+        if let MoveKind::Move = movement.kind {
             self.update_castling_rights(&movement);
         }
 
-        self.pieces.update(self.side, movement);
+        self.update_hash(&movement);
+        self.pieces.update(movement);
+    }
+
+    /// Moves the pieces back to where they were before `movement`. Anything
+    /// else (castling rights, en passant, threats, hash) must be restored by
+    /// the caller.
+    pub fn revert(&mut self, movement: &PseudoMove) {
+        let PseudoMove {
+            origin,
+            destination,
+            ref kind,
+        } = *movement;
+
+        if let MoveKind::Castle(castle) = kind {
+            let king_after = castle.king_square_after_castle(self.side);
+            let rook_after = castle.rook_square_after_castle(self.side);
+            let rook = self.castling_rook_square(*castle);
+
+            self.pieces.piece_mut(Piece::King).reset(king_after);
+            self.pieces.piece_mut(Piece::Rook).reset(rook_after);
+            self.occupancy.reset(king_after);
+            self.occupancy.reset(rook_after);
+
+            self.pieces.piece_mut(Piece::King).set(origin);
+            self.pieces.piece_mut(Piece::Rook).set(rook);
+            self.occupancy.set(origin);
+            self.occupancy.set(rook);
+            return;
+        }
+
+        self.occupancy.reset(destination);
+        self.occupancy.set(origin);
+
+        match kind {
+            MoveKind::Move => {
+                let piece = self.pieces.find(destination).unwrap();
+                let mask = self.pieces.piece_mut(piece);
+                mask.reset(destination);
+                mask.set(origin);
+            }
+            MoveKind::Promote(piece) => {
+                self.pieces.piece_mut(*piece).reset(destination);
+                self.pieces.piece_mut(Piece::Pawn).set(origin);
+            }
+            MoveKind::Castle(_) => unreachable!(),
+        }
     }
 
     fn update_castling_rights(&mut self, movement: &PseudoMove) {
@@ -112,7 +240,7 @@ impl SideState {
 
         let is_king_move = self.pieces.piece(Piece::King).get(origin);
         if is_king_move {
-            self.castling_rights = CastlingRights::None;
+            self.set_castling_rights(CastlingRights::None);
             return;
         }
 
@@ -121,10 +249,10 @@ impl SideState {
             return;
         }
 
-        if origin == Castle::QueenSide.rook_square_before_castle(self.side) {
-            self.castling_rights = self.castling_rights.without(CastlingRights::QueenSide);
-        } else if origin == Castle::KingSide.rook_square_before_castle(self.side) {
-            self.castling_rights = self.castling_rights.without(CastlingRights::KingSide);
+        if origin == self.castling_rook_square(Castle::QueenSide) {
+            self.set_castling_rights(self.castling_rights.without(CastlingRights::QueenSide));
+        } else if origin == self.castling_rook_square(Castle::KingSide) {
+            self.set_castling_rights(self.castling_rights.without(CastlingRights::KingSide));
         }
     }
 
@@ -192,7 +320,8 @@ impl SidePieces {
         Some(Piece::try_from(result as usize).ok()).flatten()
     }
 
-    pub fn update(&mut self, side: Side, movement: PseudoMove) {
+    /// Moves the pieces of a non castling move.
+    pub fn update(&mut self, movement: PseudoMove) {
         let PseudoMove {
             origin,
             destination,
@@ -211,15 +340,7 @@ impl SidePieces {
                 self.piece_mut(Piece::Pawn).reset(origin);
                 self.piece_mut(piece).set(destination);
             }
-            MoveKind::Castle(castle) => {
-                let king_mask = self.piece_mut(Piece::King);
-                king_mask.reset(origin);
-                king_mask.set(destination);
-
-                let rook_mask = self.piece_mut(Piece::Rook);
-                rook_mask.reset(castle.rook_square_before_castle(side));
-                rook_mask.set(castle.rook_square_after_castle(side));
-            }
+            MoveKind::Castle(_) => unreachable!("castles need the castling rook squares"),
         }
     }
 }
@@ -240,6 +361,12 @@ impl SidePiecesThreats {
         for (threats, (squares, piece)) in
             self.0.iter_mut().zip(my_pieces.0.iter().zip(Piece::iter()))
         {
+            if piece == Piece::Pawn {
+                *threats = movegen::pawn_attacks(side, *squares);
+                result = result.with(*threats);
+                continue;
+            }
+
             *threats = BoardMask::default();
             for square in *squares {
                 *threats = threats.with(movegen::threats(
@@ -322,35 +449,13 @@ impl CastlingRights {
             Self::KingSide | Self::Both => true,
         }
     }
+}
 
-    pub fn to_fen_str(self) -> &'static str {
-        match self {
-            Self::None => "",
-            Self::QueenSide => "q",
-            Self::KingSide => "k",
-            Self::Both => "kq",
-        }
-    }
-
-    /// Parses FEN castling rights for white and black.
-    pub fn parse_fen_from_str(castling_rights: &str) -> Option<(CastlingRights, CastlingRights)> {
-        if castling_rights == "-" {
-            return Some((CastlingRights::None, CastlingRights::None));
-        }
-
-        let mut white_cr = CastlingRights::None;
-        let mut black_cr = CastlingRights::None;
-
-        for chr in castling_rights.chars() {
-            match chr {
-                'K' => white_cr.checked_add(CastlingRights::KingSide).ok()?,
-                'Q' => white_cr.checked_add(CastlingRights::QueenSide).ok()?,
-                'k' => black_cr.checked_add(CastlingRights::KingSide).ok()?,
-                'q' => black_cr.checked_add(CastlingRights::QueenSide).ok()?,
-                _ => return None,
-            }
+impl From<Castle> for CastlingRights {
+    fn from(castle: Castle) -> Self {
+        match castle {
+            Castle::KingSide => Self::KingSide,
+            Castle::QueenSide => Self::QueenSide,
         }
-
-        Some((white_cr, black_cr))
     }
 }
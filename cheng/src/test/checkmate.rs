@@ -5,8 +5,6 @@ use crate::{
 
 #[test]
 fn test_simple_queen_check() {
-    crate::init();
-
     let mut board = Board::default();
     board.try_feed("e2e4").unwrap();
     board.try_feed("e7e5").unwrap();
@@ -27,8 +25,6 @@ fn test_simple_queen_check() {
 
 #[test]
 fn test_checkmate_fast() {
-    crate::init();
-
     // Scholar's mate.
     let mut board = Board::default();
     board.try_feed("e2e4").unwrap();
@@ -1,9 +1,16 @@
 mod checkmate;
 mod movegen;
 mod moves;
+mod perft;
+mod san;
+mod undo;
+mod zobrist;
 
 use crate::{
-    board::{Board, BoardMask, BorkedBoard, FENParsingError},
+    board::{
+        Board, BoardMask, BorkedBoard, FENParsingError, FENParsingErrorKind, FenEnPassant,
+        FenField, FenWriteOptions,
+    },
     pieces::Piece,
     side_state::SideState,
     sides::Side,
@@ -99,7 +106,11 @@ fn test_fen_parsing() {
     let misaligned_board = BorkedBoard::from_fen("8/8/8/8/8/8/7/8 w - - 0 1");
     assert_eq!(
         misaligned_board.unwrap_err(),
-        FENParsingError::InvalidAlignment
+        FENParsingError {
+            field: FenField::Placement,
+            offset: 13,
+            kind: FENParsingErrorKind::InvalidAlignment,
+        }
     );
 
     let board_fen_with_extra_spaces =
@@ -113,9 +124,16 @@ fn test_fen_generation() {
     assert_eq!(board.as_fen(), Board::DEFAULT_FEN);
 
     board.try_feed("e2e4").unwrap();
-    let expected_fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+    let expected_fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1";
     assert_eq!(board.as_fen(), expected_fen);
 
+    let options = FenWriteOptions {
+        en_passant: FenEnPassant::Always,
+        ..FenWriteOptions::default()
+    };
+    let expected_fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+    assert_eq!(board.as_fen_with(&options), expected_fen);
+
     board.try_feed("e7e5").unwrap();
     board.try_feed("e1e2").unwrap();
 
@@ -4,7 +4,7 @@ use crate::{
     movement::{Castle, MoveKind},
     side_state::CastlingRights,
     square::prelude::*,
-    BorkedBoard, FromIntoFen, Piece, PseudoMove, Side,
+    BorkedBoard, FromIntoFen, Piece, PseudoMove, Side, Square,
 };
 
 #[test]
@@ -58,6 +58,34 @@ fn test_movegen_pawn() {
     assert_eq!(moves, moves_expected);
 }
 
+#[test]
+fn test_movegen_pawns_setwise() {
+    let white = BoardMask::from([A2, E2, H5, B7]);
+    let black = BoardMask::from([A7, D7, H2]);
+    let empty = white.with(black).with(BoardMask::from(E3)).opposite();
+
+    let pushes = movegen::pawn_pushes(Side::White, white, empty);
+    assert_eq!(pushes, BoardMask::from([A3, H6, B8]));
+    let pushes = movegen::pawn_double_pushes(Side::White, white, empty);
+    assert_eq!(pushes, BoardMask::from([A4]));
+    let pushes = movegen::pawn_double_pushes(Side::Black, black, empty);
+    assert_eq!(pushes, BoardMask::from([A5, D5]));
+
+    // Pawns on the edges don't wrap around to the other side of the board.
+    let attacks = movegen::pawn_attacks(Side::White, white);
+    assert_eq!(attacks, BoardMask::from([B3, D3, F3, G6, A8, C8]));
+    let attacks = movegen::pawn_attacks(Side::Black, black);
+    assert_eq!(attacks, BoardMask::from([B6, C6, E6, G1]));
+
+    // The tables are empty for pawns on the first and last ranks.
+    for square in Square::iter_all().filter(|square| (1..7).contains(&square.rank::<usize>())) {
+        for side in [Side::White, Side::Black] {
+            let attacks = movegen::pawn_attacks(side, BoardMask::from(square));
+            assert_eq!(attacks, movegen::pawn_threats(side, square));
+        }
+    }
+}
+
 #[test]
 fn test_movegen_rook_steady() {
     let occupancy = BoardMask::from([A2, B8, D3, E1, E3, H2, H5, H7, H8]);
@@ -68,8 +96,6 @@ fn test_movegen_rook_steady() {
 
 #[test]
 fn test_relevant_occ_mask_steady() {
-    use steady::SlidingPiece;
-
     let relevant_occ_mask = Rook::relevant_occupancy(D6);
     let expected = BoardMask::from([B6, C6, E6, F6, G6, D2, D3, D4, D5, D7]);
     assert_eq!(relevant_occ_mask, expected);
@@ -78,6 +104,10 @@ fn test_relevant_occ_mask_steady() {
     let expected = BoardMask::from([B1, C1, D1, E1, F1, G1, A2, A3, A4, A5, A6, A7]);
     assert_eq!(relevant_occ_mask, expected);
 
+    let relevant_occ_mask = Rook::relevant_occupancy(A4);
+    let expected = BoardMask::from([B4, C4, D4, E4, F4, G4, A2, A3, A5, A6, A7]);
+    assert_eq!(relevant_occ_mask, expected);
+
     let relevant_occ_mask = Bishop::relevant_occupancy(H1);
     let expected = BoardMask::from([B7, C6, D5, E4, F3, G2]);
     assert_eq!(relevant_occ_mask, expected);
@@ -93,8 +123,6 @@ fn test_relevant_occ_mask_steady() {
 
 #[test]
 fn test_movegen_rook() {
-    crate::init();
-
     let occupancy = BoardMask::default();
     let moves = Rook::moves(D4, BoardMask::default(), occupancy);
     let moves_expected = BoardMask::from([D1, D2, D3, A4, B4, C4, E4, F4, G4, H4, D5, D6, D7, D8]);
@@ -123,8 +151,6 @@ fn test_movegen_bishop_steady() {
 
 #[test]
 fn test_movegen_bishop() {
-    crate::init();
-
     let occupancy = BoardMask::default();
 
     let moves = Bishop::moves(C7, BoardMask::default(), occupancy);
@@ -140,8 +166,6 @@ fn test_movegen_bishop() {
 
 #[test]
 fn test_movegen_cant_slide_to_friendly_occupation() {
-    crate::init();
-
     let friendly = BoardMask::from([A3, C1]);
     let opposite = BoardMask::from([H3, C8]);
 
@@ -153,8 +177,6 @@ fn test_movegen_cant_slide_to_friendly_occupation() {
 
 #[test]
 fn test_movegen_king_cant_move_to_threaten() {
-    crate::init();
-
     let mut board = Board::default();
     board.try_feed("b2b3").unwrap();
     board.try_feed("e7e5").unwrap();
@@ -177,7 +199,6 @@ fn test_en_passant_as_white() {
 
     let contains_en_passant_capture = board
         .moves()
-        .map(PseudoMove::from)
         .collect::<Vec<_>>()
         .contains(&"f5g6".parse().unwrap());
     assert!(contains_en_passant_capture);
@@ -197,7 +218,6 @@ fn test_en_passant_as_black() {
 
     let contains_en_passant_capture = board
         .moves()
-        .map(PseudoMove::from)
         .collect::<Vec<_>>()
         .contains(&"b4c3".parse().unwrap());
 
@@ -229,8 +249,6 @@ fn test_only_pawns_take_en_passant() {
 
 #[test]
 fn test_castling() {
-    crate::init();
-
     // https://lichess.org/analysis/4k3/8/8/8/2r5/8/1R6/R3K2R_w_KQ_-_0_1?color=white
     let mut board = BorkedBoard::from_fen("4k3/8/8/8/2r5/8/1R6/R3K2R w KQ - 0 1").unwrap();
 
@@ -269,8 +287,6 @@ fn test_castling() {
 
 #[test]
 fn test_castling_cant_castle_through_pieces() {
-    crate::init();
-
     // https://lichess.org/analysis/4k3/8/8/8/8/8/8/Rb2K2R_w_KQ_-_0_1?color=white
     let mut board = BorkedBoard::from_fen("4k3/8/8/8/8/8/8/Rb2K2R w KQ - 0 1").unwrap();
 
@@ -296,8 +312,6 @@ fn test_castling_cant_castle_through_pieces() {
 
 #[test]
 fn test_castling_canceled_after_rook_is_taken() {
-    crate::init();
-
     // https://lichess.org/analysis/4k3/8/8/8/8/8/8/Rb2K2R_w_KQ_-_0_1?color=white
     let mut board = BorkedBoard::from_fen("4k3/8/8/8/8/8/8/Rb2K2R w KQ - 0 1").unwrap();
     board.try_feed("a1a2").unwrap();
@@ -316,3 +330,57 @@ fn test_promotions() {
         .moves()
         .any(|movement| movement.kind == MoveKind::Promote(Piece::Queen)));
 }
+
+#[test]
+fn test_magic_tables_match_steady_moves() {
+    for square in Square::iter_all() {
+        let relevant_occupancy = Rook::relevant_occupancy(square);
+        for i in 0..relevant_occupancy.variations() {
+            let occupancy = relevant_occupancy.variation(i);
+            assert_eq!(
+                Rook::threats(square, occupancy, BoardMask::default()),
+                <Rook as steady::SlidingPiece>::moves(square, occupancy)
+            );
+        }
+
+        let relevant_occupancy = Bishop::relevant_occupancy(square);
+        for i in 0..relevant_occupancy.variations() {
+            let occupancy = relevant_occupancy.variation(i);
+            assert_eq!(
+                Bishop::threats(square, occupancy, BoardMask::default()),
+                <Bishop as steady::SlidingPiece>::moves(square, occupancy)
+            );
+        }
+    }
+}
+
+#[cfg(feature = "pext")]
+#[test]
+fn test_pext_matches_magics() {
+    use crate::movegen::{magic_bishop_threats, magic_rook_threats, pext};
+
+    for square in Square::iter_all() {
+        let relevant_occupancy = Rook::relevant_occupancy(square);
+        for i in 0..relevant_occupancy.variations() {
+            let occupancy = relevant_occupancy.variation(i);
+            // The software version, used without BMI2, packs the same bits.
+            assert_eq!(
+                pext::pext(occupancy, relevant_occupancy),
+                pext::pext_fallback(occupancy.into(), relevant_occupancy.into()) as usize
+            );
+            assert_eq!(
+                pext::rook_threats(square, occupancy),
+                magic_rook_threats(square, occupancy)
+            );
+        }
+
+        let relevant_occupancy = Bishop::relevant_occupancy(square);
+        for i in 0..relevant_occupancy.variations() {
+            let occupancy = relevant_occupancy.variation(i);
+            assert_eq!(
+                pext::bishop_threats(square, occupancy),
+                magic_bishop_threats(square, occupancy)
+            );
+        }
+    }
+}
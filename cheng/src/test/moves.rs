@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use crate::{
-    board::{Board, BoardMask},
+    board::{Board, BoardMask, BorkedBoard},
     movement::{MoveKind, MoveParseError, PseudoMove},
     pieces::Piece,
     square::prelude::*,
@@ -103,3 +103,38 @@ fn test_move_promotion() {
         BoardMask::from(F1),
     );
 }
+
+#[test]
+fn test_packed_move_flags() {
+    use crate::movement::{Castle, PackedMove};
+
+    let capture = PackedMove::promotion(G7, H8, Piece::Knight, true);
+    assert_eq!((capture.origin(), capture.destination()), (G7, H8));
+    assert_eq!(capture.promotion_piece(), Some(Piece::Knight));
+    assert!(capture.is_capture());
+
+    let en_passant = PackedMove::en_passant(D5, C6);
+    assert!(en_passant.is_capture() && en_passant.is_en_passant());
+    assert_eq!(en_passant.kind(), MoveKind::Move);
+
+    let castle = PackedMove::castle(E1, G1, Castle::KingSide);
+    assert_eq!(castle.kind(), MoveKind::Castle(Castle::KingSide));
+    assert!(!castle.is_capture());
+
+    assert_eq!(std::mem::size_of::<Option<PackedMove>>(), 2);
+}
+
+#[test]
+fn test_pack_matches_generated_moves() {
+    // https://www.chessprogramming.org/Perft_Results#Position_2
+    let board = BorkedBoard::from_fen(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq a3 0 1",
+    )
+    .unwrap();
+
+    let moves = board.legal_moves();
+    assert!(moves.iter().any(|movement| movement.is_en_passant()));
+    for &movement in &moves {
+        assert_eq!(board.pack(&movement.into()), movement, "{movement}");
+    }
+}
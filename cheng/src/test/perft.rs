@@ -0,0 +1,24 @@
+use crate::{Board, BorkedBoard, FromIntoFen, Perft};
+
+#[test]
+fn test_perft_table_is_reused() {
+    let mut board = BorkedBoard::from_fen(Board::DEFAULT_FEN).unwrap();
+    let perft = Perft::new(2, 1 << 20);
+    assert_eq!(perft.perft(&board, 4), 197_281);
+
+    // Every subtree under the root was stored, so a second run is served
+    // from the table.
+    for movement in board.legal_moves() {
+        let undo = board.make_packed(movement);
+        let hash = board.hash();
+        let expected = board.perft(3);
+        board.unmake(undo);
+
+        assert_eq!(perft.table.probe(hash, 3), Some(expected), "{movement}");
+    }
+    assert_eq!(perft.perft(&board, 4), 197_281);
+
+    perft.clear();
+    let _ = board.make_packed(board.legal_moves()[0]);
+    assert_eq!(perft.table.probe(board.hash(), 3), None);
+}
@@ -0,0 +1,144 @@
+use crate::{
+    board::Board,
+    movement::{MoveKind, SANParseError},
+    pieces::Piece,
+    square::prelude::*,
+    FromIntoFen, PseudoMove, SAN,
+};
+
+fn parse(board: &Board, san: &str) -> Result<PseudoMove, SANParseError> {
+    board.parse_san(san).map(PseudoMove::from)
+}
+
+#[test]
+fn test_san_parsing() {
+    let board = Board::default();
+    let movement = parse(&board, "Nf3").unwrap();
+    assert_eq!((movement.origin, movement.destination), (G1, F3));
+    let movement = parse(&board, "e4").unwrap();
+    assert_eq!((movement.origin, movement.destination), (E2, E4));
+
+    let board = Board::from_fen("4k3/8/8/3p4/2P1P3/8/8/R3K2R w KQ - 0 1").unwrap();
+    let movement = parse(&board, "exd5").unwrap();
+    assert_eq!((movement.origin, movement.destination), (E4, D5));
+    let movement = parse(&board, "cxd5").unwrap();
+    assert_eq!((movement.origin, movement.destination), (C4, D5));
+    let movement = parse(&board, "Rad1").unwrap();
+    assert_eq!((movement.origin, movement.destination), (A1, D1));
+    assert_eq!(
+        parse(&board, "O-O").unwrap().kind,
+        MoveKind::Castle(crate::Castle::KingSide)
+    );
+    assert_eq!(
+        parse(&board, "O-O-O+").unwrap().kind,
+        MoveKind::Castle(crate::Castle::QueenSide)
+    );
+
+    let board = Board::from_fen("3k4/8/8/8/8/4R3/8/4RK2 w - - 0 1").unwrap();
+    let movement = parse(&board, "R1e2").unwrap();
+    assert_eq!((movement.origin, movement.destination), (E1, E2));
+
+    let board = Board::from_fen("n3k3/1P2P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        parse(&board, "bxa8=N#").unwrap(),
+        PseudoMove {
+            origin: B7,
+            destination: A8,
+            kind: MoveKind::Promote(Piece::Knight),
+        }
+    );
+    assert_eq!(
+        parse(&board, "b8=Q+").unwrap().kind,
+        MoveKind::Promote(Piece::Queen)
+    );
+}
+
+#[test]
+fn test_san_parsing_errors() {
+    let board = Board::from_fen("3k4/8/8/8/8/4R3/8/4RK2 w - - 0 1").unwrap();
+    assert_eq!(parse(&board, "Re2"), Err(SANParseError::AmbiguousMove));
+    assert_eq!(parse(&board, "Ra8"), Err(SANParseError::IllegalMove));
+    assert_eq!(parse(&board, "O-O"), Err(SANParseError::IllegalMove));
+    assert_eq!(parse(&board, "Xe2"), Err(SANParseError::Malformed));
+    assert_eq!(parse(&board, "Re9"), Err(SANParseError::Malformed));
+    assert_eq!(parse(&board, "R=Qe2"), Err(SANParseError::Malformed));
+    assert_eq!(parse(&board, ""), Err(SANParseError::Malformed));
+
+    // The knight on d2 is pinned, so Nf3 is not ambiguous.
+    let board = Board::from_fen("4k3/8/8/8/1b6/8/3N4/4K1N1 w - - 0 1").unwrap();
+    let movement = parse(&board, "Nf3").unwrap();
+    assert_eq!(movement.origin, G1);
+
+    // A pawn reaching the last rank must say what it promotes to.
+    let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(parse(&board, "b8"), Err(SANParseError::IllegalMove));
+}
+
+#[test]
+fn test_try_feed_san() {
+    let mut board = Board::default();
+    for movement in ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6", "O-O"] {
+        board.try_feed(movement).unwrap();
+    }
+    assert_eq!(
+        board.as_fen(),
+        "r1bqkbnr/1pp2ppp/p1p5/4p3/4P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 1 5"
+    );
+
+    board.try_feed("Qd1").unwrap_err();
+    board.try_feed("d8d1").unwrap_err();
+}
+
+fn san(board: &Board, movement: &str) -> String {
+    let movement = board.validate(movement).unwrap();
+    SAN(&movement, board).to_string()
+}
+
+#[test]
+fn test_san_display() {
+    let board = Board::default();
+    assert_eq!(san(&board, "e2e4"), "e4");
+    assert_eq!(san(&board, "g1f3"), "Nf3");
+
+    let board = Board::from_fen("r3k3/8/8/3pP3/2P5/4R3/8/R3KN1R w KQq d6 0 1").unwrap();
+    assert_eq!(san(&board, "c4d5"), "cxd5");
+    assert_eq!(san(&board, "e5d6"), "exd6+");
+    // The rook on h1 can't reach d1.
+    assert_eq!(san(&board, "a1d1"), "Rd1");
+    assert_eq!(san(&board, "e3e2"), "Re2");
+    assert_eq!(san(&board, "a1a8"), "Rxa8+");
+    assert_eq!(san(&board, "e1g1"), "O-O");
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1").unwrap();
+    assert_eq!(san(&board, "a1d1"), "Rad1");
+    assert_eq!(san(&board, "f1d1"), "Rfd1");
+
+    let board = Board::from_fen("3k4/8/8/8/8/4R3/8/4RK2 w - - 0 1").unwrap();
+    assert_eq!(san(&board, "e1e2"), "R1e2");
+    assert_eq!(san(&board, "e3e2"), "R3e2");
+
+    let board = Board::from_fen("8/8/1k6/8/4Q2Q/8/8/K6Q w - - 0 1").unwrap();
+    assert_eq!(san(&board, "h4e1"), "Qh4e1");
+    assert_eq!(san(&board, "h1e1"), "Q1e1");
+    assert_eq!(san(&board, "e4e1"), "Qee1");
+
+    let board = Board::from_fen("n3k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(san(&board, "b7a8n"), "bxa8=N");
+    assert_eq!(san(&board, "b7b8q"), "b8=Q+");
+
+    let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+    assert_eq!(san(&board, "a1a8"), "Ra8#");
+    assert_eq!(san(&board, "e1c1"), "O-O-O");
+}
+
+#[test]
+fn test_san_round_trip() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+
+    for movement in board.moves() {
+        let san = SAN(&movement, &board).to_string();
+        assert_eq!(board.parse_san(&san), Ok(movement), "{san}");
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{BorkedBoard, FromIntoFen, PseudoMove};
+
+fn assert_make_unmake_restores(board: &mut BorkedBoard, depth: usize) {
+    if depth == 0 {
+        return;
+    }
+
+    for movement in board.moves().cached_moves {
+        let movement = PseudoMove::from(movement);
+        let before = board.clone();
+        let undo = board.make(&movement);
+        if !board.is_borked() {
+            assert_make_unmake_restores(board, depth - 1);
+        }
+        board.unmake(undo);
+        assert_eq!(*board, before, "{movement} was not undone properly");
+    }
+}
+
+#[test]
+fn test_make_unmake_restores_position() {
+    let fens = [
+        // https://www.chessprogramming.org/Perft_Results#Position_2
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        // https://www.chessprogramming.org/Perft_Results#Position_3
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        // https://www.chessprogramming.org/Perft_Results#Position_4
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "8/8/8/2k5/1pP5/3K4/8/8 b - c3 0 1",
+    ];
+
+    for fen in fens {
+        let mut board = BorkedBoard::from_fen(fen).unwrap();
+        assert_make_unmake_restores(&mut board, 2);
+    }
+}
+
+#[test]
+fn test_make_reports_captures() {
+    use crate::{prelude::*, Piece};
+
+    let mut board = BorkedBoard::from_fen("8/8/8/2k5/1pP5/3K4/8/8 b - c3 0 1").unwrap();
+    let undo = board.make(&"b4c3".parse().unwrap());
+    assert_eq!(undo.captured(), Some((Piece::Pawn, C4)));
+    board.unmake(undo);
+
+    let undo = board.make(&"c5c4".parse().unwrap());
+    assert_eq!(undo.captured(), Some((Piece::Pawn, C4)));
+    board.unmake(undo);
+
+    let undo = board.make(&"c5b5".parse().unwrap());
+    assert_eq!(undo.captured(), None);
+}
+
+#[test]
+fn test_make_packed_matches_make() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "8/8/8/2k5/1pP5/3K4/8/8 b - c3 0 1",
+    ];
+
+    for fen in fens {
+        let mut board = BorkedBoard::from_fen(fen).unwrap();
+        for movement in board.moves().cached_moves {
+            let mut packed = board.clone();
+            let undo = board.make(&PseudoMove::from(movement));
+            let packed_undo = packed.make_packed(movement);
+
+            assert_eq!(packed, board, "{movement}");
+            assert_eq!(packed_undo, undo, "{movement}");
+            board.unmake(undo);
+        }
+    }
+}
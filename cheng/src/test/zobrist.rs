@@ -0,0 +1,85 @@
+use crate::{board::Board, BorkedBoard, FromIntoFen};
+
+#[test]
+fn test_hash_matches_from_scratch() {
+    let mut board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    assert_eq!(board.inner().hash(), board.inner().compute_hash());
+
+    for movement in [
+        "a2a4", "b4a3", "e1c1", "h3g2", "d2h6", "g2h1q", "e5f7", "e8g8",
+    ] {
+        board.try_feed(movement).unwrap();
+        assert_eq!(board.inner().hash(), board.inner().compute_hash());
+
+        let parsed = BorkedBoard::from_fen(&board.as_fen()).unwrap();
+        assert_eq!(board.inner().hash(), parsed.hash());
+    }
+}
+
+#[test]
+fn test_hash_transposition() {
+    let mut board = Board::default();
+    let initial_hash = board.inner().hash();
+
+    board.try_feed("g1f3").unwrap();
+    assert_ne!(board.inner().hash(), initial_hash);
+
+    for movement in ["g8f6", "f3g1", "f6g8"] {
+        board.try_feed(movement).unwrap();
+    }
+    assert_eq!(board.inner().hash(), initial_hash);
+
+    let mut board = Board::default();
+    let mut transposed = Board::default();
+    for movement in ["e2e4", "e7e5", "g1f3", "b8c6"] {
+        board.try_feed(movement).unwrap();
+    }
+    for movement in ["g1f3", "e7e5", "e2e4", "b8c6"] {
+        transposed.try_feed(movement).unwrap();
+    }
+    assert_eq!(board.inner().hash(), transposed.inner().hash());
+
+    // A double push that can't be captured en passant transposes to two
+    // single pushes, the white king triangulates to lose the tempo.
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1";
+    let mut board = Board::from_fen(fen).unwrap();
+    let mut transposed = Board::from_fen(fen).unwrap();
+    board.try_feed("e2e4").unwrap();
+    for movement in [
+        "e2e3", "e8d8", "e1d1", "d8e8", "d1d2", "e8d8", "d2e1", "d8e8", "e3e4",
+    ] {
+        transposed.try_feed(movement).unwrap();
+    }
+    assert_eq!(board.inner().hash(), transposed.inner().hash());
+}
+
+#[test]
+fn test_hash_en_passant_and_castling() {
+    let with_en_passant =
+        BorkedBoard::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+            .unwrap();
+    let without_en_passant =
+        BorkedBoard::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+            .unwrap();
+    // No black pawn can capture on e3, so it's the same position.
+    assert_eq!(with_en_passant.hash(), without_en_passant.hash());
+
+    let capturable =
+        BorkedBoard::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+            .unwrap();
+    let not_capturable =
+        BorkedBoard::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+            .unwrap();
+    assert_ne!(capturable.hash(), not_capturable.hash());
+
+    let without_castling =
+        BorkedBoard::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b Kkq - 0 1").unwrap();
+    assert_ne!(without_en_passant.hash(), without_castling.hash());
+
+    let white_to_move =
+        BorkedBoard::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1")
+            .unwrap();
+    assert_ne!(without_en_passant.hash(), white_to_move.hash());
+}
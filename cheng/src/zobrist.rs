@@ -0,0 +1,101 @@
+use crate::{CastlingRights, Piece, Side, Square};
+
+// https://www.chessprogramming.org/Zobrist_Hashing
+//
+// The keys are generated at compile time with splitmix64, so they are the same
+// across builds and platforms.
+
+const PIECE_KINDS: usize = 6;
+
+const SEED: u64 = 0x6368_656E_675F_7A6B;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+struct Keys {
+    pieces: [[[u64; 64]; PIECE_KINDS]; 2],
+    king_side_castle: [u64; 2],
+    queen_side_castle: [u64; 2],
+    en_passant: [u64; 8],
+    black_to_move: u64,
+}
+
+const fn generate_keys() -> Keys {
+    let mut keys = Keys {
+        pieces: [[[0; 64]; PIECE_KINDS]; 2],
+        king_side_castle: [0; 2],
+        queen_side_castle: [0; 2],
+        en_passant: [0; 8],
+        black_to_move: 0,
+    };
+
+    let mut state = SEED;
+    let mut key;
+
+    // Rust doesn't allow for loops in const functions.
+    let mut side = 0;
+    while side < 2 {
+        let mut piece = 0;
+        while piece < PIECE_KINDS {
+            let mut square = 0;
+            while square < 64 {
+                (state, key) = splitmix64(state);
+                keys.pieces[side][piece][square] = key;
+                square += 1;
+            }
+            piece += 1;
+        }
+
+        (state, key) = splitmix64(state);
+        keys.king_side_castle[side] = key;
+        (state, key) = splitmix64(state);
+        keys.queen_side_castle[side] = key;
+        side += 1;
+    }
+
+    let mut file = 0;
+    while file < 8 {
+        (state, key) = splitmix64(state);
+        keys.en_passant[file] = key;
+        file += 1;
+    }
+
+    (_, key) = splitmix64(state);
+    keys.black_to_move = key;
+
+    keys
+}
+
+static KEYS: Keys = generate_keys();
+
+#[inline]
+pub fn piece(side: Side, piece: Piece, square: Square) -> u64 {
+    KEYS.pieces[side as usize][usize::from(piece)][square.to_index()]
+}
+
+#[inline]
+pub fn castling_rights(side: Side, rights: CastlingRights) -> u64 {
+    let mut key = 0;
+    if rights.king_side() {
+        key ^= KEYS.king_side_castle[side as usize];
+    }
+    if rights.queen_side() {
+        key ^= KEYS.queen_side_castle[side as usize];
+    }
+    key
+}
+
+#[inline]
+pub fn en_passant(square: Square) -> u64 {
+    KEYS.en_passant[square.file::<usize>()]
+}
+
+#[inline]
+pub fn black_to_move() -> u64 {
+    KEYS.black_to_move
+}
@@ -0,0 +1,57 @@
+use cheng::prelude::*;
+use cheng::{Board, BoardMask, BorkedBoard, FromIntoFen, Side, Square};
+
+fn mask(squares: &[Square]) -> BoardMask {
+    BoardMask::const_from_slice(squares)
+}
+
+#[test]
+fn test_attackers_to() {
+    let board = BorkedBoard::from_fen("4k3/8/2n5/3p4/8/3R1N2/8/4K2B w - - 0 1").unwrap();
+    assert_eq!(board.attackers_to(E4, Side::Black), mask(&[D5]));
+    assert_eq!(board.attackers_to(D4, Side::Black), mask(&[C6]));
+    assert_eq!(board.attackers_to(D5, Side::White), mask(&[D3]));
+    assert_eq!(board.attackers_to(E5, Side::White), mask(&[F3]));
+    // The knight on f3 blocks the bishop.
+    assert_eq!(board.attackers_to(G2, Side::White), mask(&[H1]));
+    assert_eq!(board.attackers_to(E4, Side::White), BoardMask::default());
+}
+
+#[test]
+fn test_checkers() {
+    let board = BorkedBoard::from_fen("4k3/8/8/8/8/5n2/8/r3K2R w K - 0 1").unwrap();
+    assert_eq!(board.checkers(), mask(&[A1, F3]));
+
+    let board = BorkedBoard::from_fen(Board::DEFAULT_FEN).unwrap();
+    assert_eq!(board.checkers(), BoardMask::default());
+}
+
+#[test]
+fn test_pinned_and_discovered_checks() {
+    let board = BorkedBoard::from_fen("4k3/4r3/8/b7/8/2N5/4B3/4K3 w - - 0 1").unwrap();
+    // The bishop on e2 shields the king from the rook, and the knight on c3
+    // from the bishop.
+    assert_eq!(board.pinned(Side::White), mask(&[E2, C3]));
+    assert_eq!(board.pinned(Side::Black), BoardMask::default());
+    // Only white pieces are in the way of the black sliders.
+    assert_eq!(
+        board.discovered_check_candidates(Side::Black),
+        BoardMask::default()
+    );
+
+    let board = BorkedBoard::from_fen("4k3/8/8/8/4N3/8/8/4RK2 w - - 0 1").unwrap();
+    assert_eq!(board.discovered_check_candidates(Side::White), mask(&[E4]));
+    assert_eq!(board.pinned(Side::Black), BoardMask::default());
+}
+
+#[test]
+fn test_xray_attacks() {
+    let board = BorkedBoard::from_fen("4k3/8/8/8/8/8/3R4/3QK3 w - - 0 1").unwrap();
+    assert_eq!(board.xray_attacks(D5, Side::White), mask(&[D1]));
+    assert_eq!(board.attackers_to(D5, Side::White), mask(&[D2]));
+
+    // Only the first piece in the way is seen through.
+    let board = BorkedBoard::from_fen("4k3/8/8/8/B7/8/2p5/3QK3 w - - 0 1").unwrap();
+    assert_eq!(board.xray_attacks(B3, Side::White), mask(&[D1]));
+    assert_eq!(board.xray_attacks(B3, Side::Black), BoardMask::default());
+}
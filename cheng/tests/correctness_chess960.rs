@@ -0,0 +1,78 @@
+use cheng::{Board, FromIntoFen, PseudoMove, SAN};
+
+#[test]
+fn test_chess960_fen() {
+    // X-FEN only uses files when `K` or `Q` would refer to another rook.
+    let board =
+        Board::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9")
+            .unwrap();
+    assert!(board.is_chess960());
+    assert_eq!(
+        board.as_fen(),
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+    );
+    assert_eq!(
+        board.as_shredder_fen(),
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9"
+    );
+    assert_eq!(Board::from_fen(&board.as_fen()).unwrap(), board);
+
+    let board = Board::from_fen("rkr4r/8/8/8/8/8/8/RKR4R w Cq - 0 1").unwrap();
+    assert_eq!(board.as_fen(), "rkr4r/8/8/8/8/8/8/RKR4R w Cq - 0 1");
+    assert_eq!(
+        board.as_shredder_fen(),
+        "rkr4r/8/8/8/8/8/8/RKR4R w Ca - 0 1"
+    );
+
+    let board = Board::from_fen("rkr4r/8/8/8/8/8/8/RKR4R w K - 0 1").unwrap();
+    assert_eq!(board.as_shredder_fen(), "rkr4r/8/8/8/8/8/8/RKR4R w H - 0 1");
+
+    // Standard positions are only played as Chess960 if asked to.
+    assert!(!Board::default().is_chess960());
+    assert_eq!(
+        Board::default().as_shredder_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1"
+    );
+}
+
+#[test]
+fn test_chess960_castle() {
+    let mut board =
+        Board::from_fen("1r2k1r1/pppppppp/8/8/8/8/PPPPPPPP/1R2K1R1 w GBgb - 0 1").unwrap();
+    let castle = board.validate("O-O").unwrap();
+    assert_eq!(PseudoMove::from(&castle).to_string(), "e1g1");
+    board.feed(castle);
+
+    let castle = board.validate("e8b8").unwrap();
+    assert_eq!(SAN(&castle, &board).to_string(), "O-O-O");
+    board.feed(castle);
+    assert_eq!(
+        board.as_fen(),
+        "2kr2r1/pppppppp/8/8/8/8/PPPPPPPP/1R3RK1 w - - 2 2"
+    );
+
+    // The king and the rook swap squares.
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/5KR1 w K - 0 1").unwrap();
+    board.try_feed("f1g1").unwrap();
+    assert_eq!(board.as_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+
+    // The king can't go through threatened squares.
+    let board = Board::from_fen("3rk3/8/8/8/8/8/8/1RK4R w KQ - 0 1").unwrap();
+    assert!(board.validate("O-O-O").is_some());
+    assert!(board.validate("O-O").is_none());
+}
+
+#[test]
+fn test_chess960_standard_position() {
+    let mut board =
+        Board::from_fen("r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4")
+            .unwrap();
+    let castle = board.validate("O-O").unwrap();
+    assert_eq!(PseudoMove::from(&castle).to_string(), "e1g1");
+    assert!(board.validate("e1h1").is_some());
+
+    board.set_chess960(true);
+    let castle = board.validate("O-O").unwrap();
+    assert_eq!(PseudoMove::from(&castle).to_string(), "e1h1");
+    assert!(board.clone().try_feed("e1g1").is_err());
+}
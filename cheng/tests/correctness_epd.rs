@@ -0,0 +1,132 @@
+use cheng::{
+    Board, Epd, EpdError, EpdOperation, FENParsingError, FENParsingErrorKind, FromIntoFen,
+    PseudoMove,
+};
+
+const EPDS: [&str; 4] = [
+    r#"r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - bm Bb5 Bc4; id "ruy; or italian";"#,
+    r#"6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra8#; am Kf1; ce 32767; acd 3; pv Ra8#; c0 "back rank"; c1 "mate";"#,
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - D1 20; D2 400; D3 8902;",
+    "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 hmvc 0; fmvn 2; noop;",
+];
+
+fn parse(epd: &str) -> Epd {
+    epd.parse().unwrap()
+}
+
+fn uci(moves: &[PseudoMove]) -> Vec<String> {
+    moves.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_epd_round_trip() {
+    for epd in EPDS {
+        assert_eq!(parse(epd).to_string(), epd);
+    }
+}
+
+#[test]
+fn test_epd_operations() {
+    let epd = parse(EPDS[0]);
+    assert_eq!(
+        epd.board,
+        Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 1")
+            .unwrap()
+    );
+    assert_eq!(epd.id(), Some("ruy; or italian"));
+    assert_eq!(uci(&epd.best_moves().unwrap()), ["f1b5", "f1c4"]);
+    assert_eq!(epd.avoid_moves().unwrap(), []);
+
+    let epd = parse(EPDS[1]);
+    assert_eq!(uci(&epd.best_moves().unwrap()), ["a1a8"]);
+    assert_eq!(uci(&epd.avoid_moves().unwrap()), ["g1f1"]);
+    assert_eq!(uci(&epd.predicted_variation().unwrap()), ["a1a8"]);
+    assert_eq!(epd.centipawn_evaluation(), Some(32767));
+    assert_eq!(epd.analysis_depth(), Some(3));
+    assert_eq!(epd.comment(0), Some("back rank"));
+    assert_eq!(epd.comment(1), Some("mate"));
+    assert_eq!(epd.comment(2), None);
+    assert_eq!(epd.id(), None);
+
+    let epd = parse(EPDS[2]);
+    assert_eq!(epd.perft(1), Some(20));
+    assert_eq!(epd.perft(3), Some(8902));
+    assert_eq!(epd.perft(4), None);
+}
+
+#[test]
+fn test_epd_clocks() {
+    let epd = parse(EPDS[3]);
+    assert_eq!(
+        epd.board.as_fen(),
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+    );
+    assert_eq!(epd.operation("noop"), Some(&[][..]));
+
+    let epd = parse("4k3/8/8/8/8/8/8/4K3 b - - hmvc 12; fmvn 40;");
+    assert_eq!(epd.board.as_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 12 40");
+}
+
+#[test]
+fn test_epd_lenient_separators() {
+    let epd = parse(r#"  4k3/8/8/8/8/8/8/4K3   w  -  -   bm  Kd2 ;id   "kings""#);
+    assert_eq!(uci(&epd.best_moves().unwrap()), ["e1d2"]);
+    assert_eq!(epd.id(), Some("kings"));
+    assert_eq!(
+        epd.to_string(),
+        r#"4k3/8/8/8/8/8/8/4K3 w - - bm Kd2; id "kings";"#
+    );
+}
+
+#[test]
+fn test_epd_set_operation() {
+    let mut epd = Epd::new(Board::default());
+    epd.set_operation("id", vec!["start".to_string()]);
+    epd.set_operation("D1", vec!["20".to_string()]);
+    epd.set_operation("id", vec!["initial position".to_string()]);
+
+    assert_eq!(
+        epd.operations[0],
+        EpdOperation {
+            opcode: "id".to_string(),
+            operands: vec!["initial position".to_string()],
+        }
+    );
+    assert_eq!(
+        epd.to_string(),
+        r#"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - id "initial position"; D1 20;"#
+    );
+}
+
+#[test]
+fn test_epd_errors() {
+    let error = |epd: &str| epd.parse::<Epd>().unwrap_err();
+
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w -"), EpdError::MissingField);
+    assert!(matches!(
+        error("4k3/8/8/8/8/8/8/4K4 w - -"),
+        EpdError::InvalidFen(_)
+    ));
+    assert!(matches!(
+        error("4k3/8/8/8/8/8/8/r3K3 b - -"),
+        EpdError::InvalidFen(FENParsingError {
+            kind: FENParsingErrorKind::SideNotToMoveInCheck,
+            ..
+        })
+    ));
+    assert_eq!(
+        error("4k3/8/8/8/8/8/8/4K3 w - - 1bm e4;"),
+        EpdError::InvalidOpcode
+    );
+    assert_eq!(
+        error(r#"4k3/8/8/8/8/8/8/4K3 w - - id "open;"#),
+        EpdError::UnterminatedString
+    );
+    assert_eq!(
+        error("4k3/8/8/8/8/8/8/4K3 w - - hmvc x;"),
+        EpdError::InvalidClock
+    );
+
+    let epd = parse("4k3/8/8/8/8/8/8/4K3 w - - bm Qd1;");
+    assert!(epd.best_moves().is_err());
+}
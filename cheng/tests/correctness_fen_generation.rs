@@ -1,9 +1,10 @@
 use cheng::prelude::*;
-use cheng::{BorkedBoard, CastlingRights, FromIntoFen, Side};
+use cheng::{
+    BorkedBoard, CastlingRights, FenCastling, FenEnPassant, FenWriteOptions, FromIntoFen, Side,
+};
 
 #[test]
 fn test_fen_generation() {
-    cheng::init();
     // https://lichess.org/pOzXhLHW/white#86
     let mut board = BorkedBoard::from_fen("8/8/5r2/p1kP1p2/1p1R1K2/1P6/P7/8 w - - 4 44").unwrap();
 
@@ -18,7 +19,6 @@ fn test_fen_generation() {
 
 #[test]
 fn test_fen_parse_en_passant() {
-    cheng::init();
     // https://lichess.org/CHxlnq14/white#40
     let mut board =
         BorkedBoard::from_fen("4rrk1/p2q2bp/1p1pR3/2pP1p2/2Q2P2/3Pp1P1/PP4BP/4R1K1 w - c6 0 21")
@@ -31,7 +31,6 @@ fn test_fen_parse_en_passant() {
 
 #[test]
 fn test_fen_parse_invalid_kqkq() {
-    cheng::init();
     let board = BorkedBoard::from_fen("4k3/8/8/8/8/8/8/4K3 w KQkq - 0 1").unwrap();
     assert_eq!(
         board.side(Side::White).castling_rights,
@@ -52,3 +51,52 @@ fn test_fen_parse_invalid_kqkq() {
         CastlingRights::None
     );
 }
+
+#[test]
+fn test_fen_write_en_passant() {
+    let with = |fen: &str, en_passant| {
+        let options = FenWriteOptions {
+            en_passant,
+            ..FenWriteOptions::default()
+        };
+        BorkedBoard::from_fen(fen).unwrap().as_fen_with(&options)
+    };
+
+    // No pawn to capture.
+    let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+    let without = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1";
+    assert_eq!(with(fen, FenEnPassant::Always), fen);
+    assert_eq!(with(fen, FenEnPassant::Capturable), without);
+    assert_eq!(with(fen, FenEnPassant::Legal), without);
+
+    // The capture would leave the king in check from the rook.
+    let fen = "8/8/8/K2pP2r/8/8/8/4k3 w - d6 0 1";
+    let without = "8/8/8/K2pP2r/8/8/8/4k3 w - - 0 1";
+    assert_eq!(with(fen, FenEnPassant::Always), fen);
+    assert_eq!(with(fen, FenEnPassant::Capturable), fen);
+    assert_eq!(with(fen, FenEnPassant::Legal), without);
+
+    let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+    assert_eq!(with(fen, FenEnPassant::Legal), fen);
+    assert_eq!(BorkedBoard::from_fen(fen).unwrap().as_fen(), fen);
+}
+
+#[test]
+fn test_fen_write_castling_and_counters() {
+    let board =
+        BorkedBoard::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9")
+            .unwrap();
+
+    assert_eq!(
+        board.as_fen(),
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+    );
+    assert_eq!(
+        board.as_fen_with(&FenWriteOptions {
+            castling: FenCastling::Shredder,
+            move_counters: false,
+            ..FenWriteOptions::default()
+        }),
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf -"
+    );
+}
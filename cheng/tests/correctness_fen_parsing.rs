@@ -0,0 +1,248 @@
+use cheng::{
+    Board, BorkedBoard, FENParsingError, FENParsingErrorKind, FenEnPassant, FenField,
+    FenValidation, FenWriteOptions, FromIntoFen, Side,
+};
+
+fn strict_error(fen: &str) -> FENParsingError {
+    Board::from_fen(fen).unwrap_err()
+}
+
+fn error(field: FenField, offset: usize, kind: FENParsingErrorKind) -> FENParsingError {
+    FENParsingError {
+        field,
+        offset,
+        kind,
+    }
+}
+
+#[test]
+fn test_fen_syntax_errors() {
+    use FENParsingErrorKind::*;
+    use FenField::*;
+
+    let cases = [
+        ("", Placement, 0, SquareUnderflow),
+        ("4k3/8/8/8/8/8/8/4K3", Turn, 19, MissingPart),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - 0",
+            FullMoveClock,
+            27,
+            MissingPart,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1 x",
+            FullMoveClock,
+            29,
+            TooManyParts,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3/8 w - - 0 1",
+            Placement,
+            20,
+            SquareOverflow,
+        ),
+        (
+            "4k4/8/8/8/8/8/8/4K3 w - - 0 1",
+            Placement,
+            2,
+            SquareOverflow,
+        ),
+        ("4k3/8/8/8/8/8/8 w - - 0 1", Placement, 15, SquareUnderflow),
+        ("4k3/8/8/8/8/8/8/4X3 w - - 0 1", Placement, 17, UnknownPiece),
+        (
+            "4k3/8/8/8/8/8/8/0000K3 w - - 0 1",
+            Placement,
+            16,
+            UnknownPiece,
+        ),
+        (
+            "4k3/8/8/8/8/7/8/4K3 w - - 0 1",
+            Placement,
+            13,
+            InvalidAlignment,
+        ),
+        ("4k3/8/8/8/8/8/8/4K3 x - - 0 1", Turn, 20, InvalidTurn),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w X - 0 1",
+            Castling,
+            22,
+            InvalidCastleRights,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - e9 0 1",
+            EnPassant,
+            24,
+            WrongEnPassantSquare,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - x 1",
+            HalfMoveClock,
+            26,
+            InvalidHalfMoveClock,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 x",
+            FullMoveClock,
+            28,
+            InvalidFullMoveClock,
+        ),
+    ];
+
+    for (fen, field, offset, kind) in cases {
+        let expected = error(field, offset, kind);
+        assert_eq!(strict_error(fen), expected, "{fen}");
+        assert_eq!(BorkedBoard::from_fen(fen).unwrap_err(), expected, "{fen}");
+    }
+}
+
+#[test]
+fn test_fen_offsets_count_leading_whitespace() {
+    assert_eq!(
+        strict_error("  4k3/8/8/8/8/8/8/4K3 w - - 0 x"),
+        error(
+            FenField::FullMoveClock,
+            30,
+            FENParsingErrorKind::InvalidFullMoveClock
+        )
+    );
+}
+
+#[test]
+fn test_fen_strict_validation() {
+    use FENParsingErrorKind::*;
+    use FenField::*;
+
+    let cases = [
+        (
+            "4k2P/8/8/8/8/8/8/4K3 w - - 0 1",
+            Placement,
+            3,
+            PawnOnBackRank,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/p3K3 w - - 0 1",
+            Placement,
+            16,
+            PawnOnBackRank,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/8 w - - 0 1",
+            Placement,
+            0,
+            MissingKing(Side::White),
+        ),
+        (
+            "8/8/8/8/8/8/8/4K3 w - - 0 1",
+            Placement,
+            0,
+            MissingKing(Side::Black),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/3KK3 w - - 0 1",
+            Placement,
+            18,
+            TooManyKings(Side::White),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/r3K3 b - - 0 1",
+            Turn,
+            21,
+            SideNotToMoveInCheck,
+        ),
+        (
+            "4k3/8/8/3p4/8/8/8/4K3 w - e6 0 1",
+            EnPassant,
+            26,
+            ImpossibleEnPassant,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - e6 0 1",
+            EnPassant,
+            24,
+            ImpossibleEnPassant,
+        ),
+        (
+            "4k3/4p3/8/4p3/8/8/8/4K3 w - e6 0 1",
+            EnPassant,
+            28,
+            ImpossibleEnPassant,
+        ),
+        (
+            "4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1",
+            EnPassant,
+            26,
+            ImpossibleEnPassant,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+            Castling,
+            22,
+            ImpossibleCastleRights,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/R3K3 w Qk - 0 1",
+            Castling,
+            24,
+            ImpossibleCastleRights,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/R3K3 w A-B - 0 1",
+            Castling,
+            24,
+            InvalidCastleRights,
+        ),
+    ];
+
+    for (fen, field, offset, kind) in cases {
+        assert_eq!(strict_error(fen), error(field, offset, kind), "{fen}");
+    }
+}
+
+#[test]
+fn test_fen_lenient_validation() {
+    // Positions rejected in strict mode that are still accepted leniently.
+    let lenient = [
+        "4k2P/8/8/8/8/8/8/4K3 w - - 0 1",
+        "8/8/8/8/8/8/8/8 w - - 0 1",
+        "4k3/8/8/3p4/8/8/8/4K3 w - e6 0 1",
+        "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+    ];
+
+    for fen in lenient {
+        assert!(BorkedBoard::from_fen(fen).is_ok(), "{fen}");
+        assert!(
+            Board::from_fen_with(fen, FenValidation::Lenient).is_ok(),
+            "{fen}"
+        );
+        assert!(Board::from_fen(fen).is_err(), "{fen}");
+    }
+
+    // A `Board` can't be in a borked position, even when parsed leniently.
+    let borked = "4k3/8/8/8/8/8/8/r3K3 b - - 0 1";
+    assert!(BorkedBoard::from_fen(borked).unwrap().is_borked());
+    assert_eq!(
+        Board::from_fen_with(borked, FenValidation::Lenient).unwrap_err(),
+        error(
+            FenField::Turn,
+            21,
+            FENParsingErrorKind::SideNotToMoveInCheck
+        )
+    );
+}
+
+#[test]
+fn test_fen_strict_accepts_valid_positions() {
+    let valid = [
+        Board::DEFAULT_FEN,
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+    ];
+
+    let options = FenWriteOptions {
+        en_passant: FenEnPassant::Always,
+        ..FenWriteOptions::default()
+    };
+    for fen in valid {
+        assert_eq!(Board::from_fen(fen).unwrap().as_fen_with(&options), fen);
+    }
+}
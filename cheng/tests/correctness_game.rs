@@ -0,0 +1,112 @@
+use cheng::{Board, FromIntoFen, Game, PgnGame, PgnMove, TryFeedError};
+
+#[test]
+fn correctness_game_undo_redo() {
+    let mut game = Game::default();
+    assert!(!game.undo());
+    assert!(!game.redo());
+
+    for movement in ["e4", "e5", "Nf3"] {
+        game.try_play(movement).unwrap();
+    }
+    game.try_play("Ke1").unwrap_err();
+    assert_eq!(game.ply(), 3);
+    let after_nf3 = game.board().as_fen();
+
+    assert!(game.undo());
+    assert!(game.undo());
+    assert_eq!(
+        game.board().as_fen(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+    );
+    assert_eq!(game.san_moves(), ["e4", "e5", "Nf3"]);
+
+    assert!(game.redo());
+    assert!(game.redo());
+    assert!(!game.redo());
+    assert_eq!(game.board().as_fen(), after_nf3);
+
+    assert!(game.goto(0));
+    assert_eq!(game.board(), &Board::default());
+    assert!(game.goto(2));
+    assert_eq!(game.ply(), 2);
+    assert!(!game.goto(4));
+    assert_eq!(game.ply(), 2);
+}
+
+#[test]
+fn correctness_game_variations() {
+    let mut game = Game::default();
+    for movement in ["e4", "e5", "Nf3", "Nc6"] {
+        game.try_play(movement).unwrap();
+    }
+
+    game.goto(2);
+    game.try_play("Nc3").unwrap();
+    assert_eq!(game.san_moves(), ["e4", "e5", "Nc3"]);
+
+    game.undo();
+    assert_eq!(game.variations(), ["Nf3", "Nc3"]);
+
+    // Playing a move that already exists reuses it.
+    game.try_play("g1f3").unwrap();
+    assert_eq!(game.san_moves(), ["e4", "e5", "Nf3", "Nc6"]);
+    game.undo();
+    assert_eq!(game.variations().len(), 2);
+
+    assert!(game.enter_variation(1));
+    assert_eq!(game.san_moves(), ["e4", "e5", "Nc3"]);
+    game.undo();
+    assert!(!game.enter_variation(2));
+}
+
+#[test]
+fn correctness_game_pgn() {
+    let pgn: PgnGame = r#"[Event "Casual"]
+[Site "?"]
+[White "Me"]
+[Black "Someone"]
+[Result "*"]
+[Annotator "Someone else"]
+
+1. e4 e5 2. Nf3 (2. Nc3 Nf6 (2... Nc6)) 2... Nc6 *"#
+        .parse()
+        .unwrap();
+
+    let mut game = Game::try_from_pgn(&pgn).unwrap();
+    assert_eq!(game.tags(), pgn.tags);
+    assert_eq!(game.ply(), 0);
+    assert_eq!(game.san_moves(), ["e4", "e5", "Nf3", "Nc6"]);
+
+    game.goto(2);
+    assert_eq!(game.variations(), ["Nf3", "Nc3"]);
+    game.enter_variation(1);
+    assert_eq!(game.variations(), ["Nf6", "Nc6"]);
+
+    assert_eq!(game.to_pgn(), pgn);
+    assert_eq!(game.to_pgn().to_string(), pgn.to_string());
+
+    // Moves built by hand may be illegal.
+    let mut illegal = PgnGame::new(Board::default());
+    illegal.moves.push(PgnMove {
+        movement: "e2e5".parse().unwrap(),
+        san: "e5".to_string(),
+        nags: Vec::new(),
+        comments_before: Vec::new(),
+        comments: Vec::new(),
+        variations: Vec::new(),
+    });
+    assert!(matches!(
+        Game::try_from_pgn(&illegal),
+        Err(TryFeedError::InvalidMove)
+    ));
+
+    let mut game = Game::default();
+    for movement in ["f3", "e5", "g4", "Qh4"] {
+        game.try_play(movement).unwrap();
+    }
+    assert_eq!(
+        game.to_pgn().to_string().lines().last(),
+        Some("1. f3 e5 2. g4 Qh4# 0-1")
+    );
+}
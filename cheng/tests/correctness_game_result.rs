@@ -1,23 +1,84 @@
-use cheng::GameResult;
 use cheng::{Board, FromIntoFen};
+use cheng::{ClaimDrawError, DrawReason, GameResult};
 
 #[test]
 fn correctness_game_result_50_move_draw() {
     // https://lichess.org/analysis/fromPosition/8/2p1k3/8/1P2K3/8/8/8/8_w_-_-_98_1
-    cheng::init();
 
     let mut board = Board::from_fen("8/2p1k3/8/1P2K3/8/8/8/8 w - - 98 1").unwrap();
     board.try_feed("e5f5").unwrap();
     board.try_feed("e7f7").unwrap();
 
-    assert_eq!(board.result(), GameResult::Draw);
+    // The fifty-move rule must be claimed, so the game goes on.
+    assert_eq!(board.result(), GameResult::Undecided);
+    assert_eq!(board.claimable_draws(), vec![DrawReason::FiftyMove]);
+    board.claim_draw(DrawReason::Threefold).unwrap_err();
+
+    board.claim_draw(DrawReason::FiftyMove).unwrap();
+    assert_eq!(
+        board.result(),
+        GameResult::Draw {
+            reason: DrawReason::FiftyMove
+        }
+    );
     board.try_feed("f5e5").unwrap_err();
 }
 
+#[test]
+fn correctness_game_result_75_move_draw() {
+    let mut board = Board::from_fen("8/2p1k3/8/1P2K3/8/8/8/8 w - - 148 1").unwrap();
+    board.try_feed("e5f5").unwrap();
+    assert_eq!(board.result(), GameResult::Undecided);
+    board.try_feed("e7f7").unwrap();
+
+    assert_eq!(
+        board.result(),
+        GameResult::Draw {
+            reason: DrawReason::SeventyFiveMove
+        }
+    );
+    board.try_feed("f5e5").unwrap_err();
+}
+
+#[test]
+fn correctness_game_result_75_move_checkmate() {
+    // Checkmate on the 150th halfmove still wins the game.
+
+    let mut board = Board::from_fen("7k/8/6K1/8/8/8/8/R7 w - - 149 1").unwrap();
+    board.try_feed("a1a8").unwrap();
+    assert_eq!(
+        board.result(),
+        GameResult::Checkmate {
+            winner: cheng::Side::White
+        }
+    );
+}
+
+#[test]
+fn correctness_game_result_draw_by_agreement() {
+    let mut board = Board::default();
+    assert!(board.claimable_draws().is_empty());
+    assert_eq!(
+        board.claim_draw(DrawReason::FiftyMove),
+        Err(ClaimDrawError::NotClaimable)
+    );
+
+    board.claim_draw(DrawReason::Agreement).unwrap();
+    assert_eq!(
+        board.result(),
+        GameResult::Draw {
+            reason: DrawReason::Agreement
+        }
+    );
+    assert_eq!(
+        board.claim_draw(DrawReason::Agreement),
+        Err(ClaimDrawError::GameOver)
+    );
+}
+
 #[test]
 fn correctness_game_result_50_move_draw_capture() {
     // https://lichess.org/analysis/fromPosition/4k3/8/8/1p6/8/2b5/3N4/4K3_b_-_-_99_1
-    cheng::init();
 
     let mut board = Board::from_fen("4k3/8/8/1p6/8/2b5/3N4/4K3 b - - 99 1").unwrap();
     board.try_feed("c3d2").unwrap();
@@ -28,18 +89,140 @@ fn correctness_game_result_50_move_draw_capture() {
 #[test]
 fn correctness_game_result_stalemate() {
     // https://lichess.org/analysis/7k/8/8/6Q1/8/8/8/4K3_w_-_-_0_1?color=white
-    cheng::init();
 
     let mut board = Board::from_fen("7k/8/8/6Q1/8/8/8/4K3 w - - 0 1").unwrap();
     board.try_feed("g5g6").unwrap();
-    assert_eq!(board.result(), GameResult::Draw);
+    assert_eq!(
+        board.result(),
+        GameResult::Draw {
+            reason: DrawReason::Stalemate
+        }
+    );
 }
 
 #[test]
 fn correctness_move_is_invalid() {
-    cheng::init();
-
     let mut board = Board::default();
     let err = board.try_feed("e2f5");
     assert!(err.is_err());
 }
+
+#[test]
+fn correctness_game_result_repetition() {
+    let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+
+    let mut board = Board::default();
+    assert_eq!(board.repetition_count(), 1);
+
+    for movement in shuffle {
+        board.try_feed(movement).unwrap();
+    }
+    assert_eq!(board.repetition_count(), 2);
+    assert!(!board.is_threefold_repetition());
+
+    for movement in shuffle {
+        board.try_feed(movement).unwrap();
+    }
+    assert_eq!(board.repetition_count(), 3);
+    assert!(board.is_threefold_repetition());
+    // Threefold repetition must be claimed, so the game goes on.
+    assert_eq!(board.result(), GameResult::Undecided);
+    assert_eq!(board.claimable_draws(), vec![DrawReason::Threefold]);
+
+    for movement in shuffle.iter().cycle().take(shuffle.len() * 2) {
+        board.try_feed(*movement).unwrap();
+    }
+    assert_eq!(board.repetition_count(), 5);
+    assert_eq!(
+        board.result(),
+        GameResult::Draw {
+            reason: DrawReason::Fivefold
+        }
+    );
+    board.try_feed("g1f3").unwrap_err();
+}
+
+#[test]
+fn correctness_game_result_repetition_reset_by_pawn_move() {
+    let mut board = Board::default();
+    for movement in ["g1f3", "g8f6", "f3g1", "f6g8", "e2e3", "e7e6"] {
+        board.try_feed(movement).unwrap();
+    }
+    assert_eq!(board.repetition_count(), 1);
+
+    for movement in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+        board.try_feed(movement).unwrap();
+    }
+    assert_eq!(board.repetition_count(), 2);
+}
+
+#[test]
+fn correctness_game_result_insufficient_material() {
+    // https://lichess.org/analysis/4k3/8/8/8/8/8/4q3/4K3_w_-_-_0_1
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/4q3/4K3 w - - 0 1").unwrap();
+    board.try_feed("e1e2").unwrap();
+    assert_eq!(
+        board.result(),
+        GameResult::Draw {
+            reason: DrawReason::InsufficientMaterial
+        }
+    );
+
+    let draws = [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KN2 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KB2 b - - 0 1",
+        // Both bishops are on dark squares.
+        "4kb2/8/8/8/8/8/8/4K1B1 w - - 0 1",
+    ];
+
+    for fen in draws {
+        assert_eq!(
+            Board::from_fen(fen).unwrap().result(),
+            GameResult::Draw {
+                reason: DrawReason::InsufficientMaterial
+            },
+            "{fen}"
+        );
+    }
+
+    let undecided = [
+        "4k3/8/8/8/8/8/8/4KR2 w - - 0 1",
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/3NKN2 w - - 0 1",
+        // Bishops on opposite colours can still checkmate.
+        "4kb2/8/8/8/8/8/8/4KB2 w - - 0 1",
+        "4kn2/8/8/8/8/8/8/4KB2 w - - 0 1",
+    ];
+
+    for fen in undecided {
+        assert_eq!(
+            Board::from_fen(fen).unwrap().result(),
+            GameResult::Undecided,
+            "{fen}"
+        );
+    }
+}
+
+#[test]
+fn correctness_game_result_dead_position() {
+    let board = Board::from_fen("8/8/8/1k6/p1p1p1p1/P1P1P1P1/8/3K4 w - - 0 1").unwrap();
+    assert_eq!(
+        board.result(),
+        GameResult::Draw {
+            reason: DrawReason::DeadPosition
+        }
+    );
+
+    // The black king can walk to c4 and then capture on a3.
+    let board = Board::from_fen("8/8/8/1k6/p3p3/P3P3/8/3K4 w - - 0 1").unwrap();
+    assert_eq!(board.result(), GameResult::Undecided);
+
+    // The h-pawn is free to move.
+    let board = Board::from_fen("8/8/8/1k6/p1p1p3/P1P1P3/7P/3K4 w - - 0 1").unwrap();
+    assert_eq!(board.result(), GameResult::Undecided);
+
+    // A rook can always break through.
+    let board = Board::from_fen("8/8/8/1k6/p1p1p1p1/P1P1P1P1/8/3K3R w - - 0 1").unwrap();
+    assert_eq!(board.result(), GameResult::Undecided);
+}
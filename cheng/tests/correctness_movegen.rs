@@ -1,11 +1,10 @@
 use cheng::prelude::*;
-use cheng::PseudoMove;
 use cheng::{BorkedBoard, FromIntoFen};
 use cheng::{Castle, MoveKind};
+use cheng::{PackedMove, PseudoMove};
 
 #[test]
 fn test_try_feed_invalid_castle() {
-    cheng::init();
     // https://lichess.org/analysis/rnb1k1nr/pppp1ppp/4pq2/8/1bB1P3/5N2/PPPP1PPP/RNBQK2R_w_-_-
     let board =
         BorkedBoard::from_fen("rnb1k1nr/pppp1ppp/4pq2/8/1bB1P3/5N2/PPPP1PPP/RNBQK2R w - - 4 4")
@@ -36,3 +35,45 @@ fn test_try_feed_invalid_castle() {
         kind: MoveKind::Castle(Castle::KingSide)
     }));
 }
+
+#[test]
+fn test_legal_moves_en_passant_discovered_check() {
+    // Capturing en passant would remove both pawns between the king and the rook.
+    let board = BorkedBoard::from_fen("8/8/8/K1pP3r/8/8/8/7k w - c6 0 2").unwrap();
+    let moves = board.legal_moves();
+    assert!(moves.iter().any(|movement| movement.origin() == D5));
+    assert!(!moves.iter().any(|movement| movement.destination() == C6));
+
+    // The pawn giving check can be captured en passant.
+    let board = BorkedBoard::from_fen("8/8/8/2pP4/1K6/8/8/7k w - c6 0 2").unwrap();
+    assert!(board
+        .legal_moves()
+        .contains(&PackedMove::en_passant(D5, C6)));
+}
+
+#[test]
+fn test_legal_moves_double_check() {
+    let board = BorkedBoard::from_fen("4k3/8/8/8/8/5n2/8/r3K2R w K - 0 1").unwrap();
+    let mut destinations: Vec<_> = board
+        .legal_moves()
+        .into_iter()
+        .map(|movement| {
+            assert_eq!(movement.origin(), E1);
+            movement.destination()
+        })
+        .collect();
+    destinations.sort_by_key(|square| square.to_index());
+    assert_eq!(destinations, [E2, F2]);
+}
+
+#[test]
+fn test_movegen_from_multiple_threads() {
+    // The sliding piece tables are built by whichever thread needs them first.
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| cheng::Board::default().perft(3)))
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 8_902);
+    }
+}
@@ -0,0 +1,236 @@
+use cheng::{
+    Board, FromIntoFen, PgnErrorKind, PgnGame, PgnReader, PgnResult, PgnWriteOptions, SANParseError,
+};
+
+const GAMES: &str = r#"[Event "F/S Return Match"]
+[Site "Belgrade, Serbia JUG"]
+[Date "1992.11.04"]
+[Round "29"]
+[White "Fischer, Robert J."]
+[Black "Spassky, Boris V."]
+[Result "1/2-1/2"]
+[ECO "C95"]
+
+{Opening comment} 1. e4 e5 2. Nf3 Nc6 3. Bb5 {This opening is called the Ruy Lopez.}
+3... a6 4. Ba4 Nf6 5. O-O Be7 $1 6. Re1 b5 7. Bb3 d6!? 8. c3 O-O
+(8... Bg4 9. d3 (9. h3 Bh5) 9... O-O)
+9. h3 ; line comment
+Nb8 10. d4 Nbd7 1/2-1/2
+
+% This line is escaped.
+[Event "Second"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "*"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"]
+
+1. e4 Kd7 *
+"#;
+
+#[test]
+fn correctness_pgn_read_games() {
+    let games: Vec<_> = PgnReader::new(GAMES.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(games.len(), 2);
+
+    let game = &games[0];
+    assert_eq!(game.tags.len(), 8);
+    assert_eq!(game.tag("White"), Some("Fischer, Robert J."));
+    assert_eq!(game.tag("ECO"), Some("C95"));
+    assert_eq!(game.result, PgnResult::Draw);
+
+    let moves: Vec<_> = game.moves.iter().map(|m| m.san.as_str()).collect();
+    assert_eq!(moves.len(), 20);
+    assert_eq!(moves[..5], ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+    assert_eq!(moves[19], "Nbd7");
+
+    assert_eq!(game.moves[0].comments_before, ["Opening comment"]);
+    assert_eq!(
+        game.moves[4].comments,
+        ["This opening is called the Ruy Lopez."]
+    );
+    assert_eq!(game.moves[9].nags, [1]);
+    assert_eq!(game.moves[13].nags, [5]);
+    assert_eq!(game.moves[16].comments, ["line comment"]);
+
+    let castle = &game.moves[15];
+    assert_eq!(castle.san, "O-O");
+    assert_eq!(castle.variations.len(), 1);
+
+    let variation = &castle.variations[0];
+    let variation_moves: Vec<_> = variation.iter().map(|m| m.san.as_str()).collect();
+    assert_eq!(variation_moves, ["Bg4", "d3", "O-O"]);
+    assert_eq!(variation[1].variations.len(), 1);
+    assert_eq!(variation[1].variations[0][1].san, "Bh5");
+
+    let game = &games[1];
+    assert_eq!(game.result, PgnResult::Unknown);
+    assert_eq!(
+        game.initial_board.as_fen(),
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"
+    );
+    assert_eq!(game.moves.len(), 2);
+}
+
+#[test]
+fn correctness_pgn_errors() {
+    let err = "1. e4 e5 2. Ke3 *".parse::<PgnGame>().unwrap_err();
+    assert_eq!((err.line, err.column), (1, 13));
+    assert_eq!(
+        err.kind,
+        PgnErrorKind::IllegalMove(SANParseError::IllegalMove)
+    );
+
+    let err = "[Event \"?\"]\n\n1. e4 e5\n2. Nf3 Nf3 *"
+        .parse::<PgnGame>()
+        .unwrap_err();
+    assert_eq!((err.line, err.column), (4, 8));
+
+    let err = "1. e4 (1. d4 *".parse::<PgnGame>().unwrap_err();
+    assert_eq!(err.kind, PgnErrorKind::UnmatchedParenthesis);
+
+    let err = "1. e4 e5 {unterminated *".parse::<PgnGame>().unwrap_err();
+    assert_eq!((err.line, err.column), (1, 10));
+    assert_eq!(err.kind, PgnErrorKind::UnterminatedComment);
+
+    let err = "[Event ?]\n1. e4 *".parse::<PgnGame>().unwrap_err();
+    assert_eq!(err.kind, PgnErrorKind::MalformedTag);
+
+    let err = "1. e4 e5".parse::<PgnGame>().unwrap_err();
+    assert_eq!(err.kind, PgnErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn correctness_pgn_skips_broken_games() {
+    let pgn = "[Event \"Broken\"]\n\n1. e4 e4 *\n\n[Event \"Fine\"]\n\n1. d4 d5 1-0\n";
+    let mut reader = PgnReader::new(pgn.as_bytes());
+
+    assert!(reader.next().unwrap().is_err());
+    let game = reader.next().unwrap().unwrap();
+    assert_eq!(game.tag("Event"), Some("Fine"));
+    assert_eq!(game.result, PgnResult::WhiteWins);
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn correctness_pgn_game_without_result() {
+    let pgn = "[Event \"a\"]\n\n1. e4 e5\n\n[Event \"b\"]\n\n1. d4 d5 1-0\n\n[Event \"c\"]\n\n1. c4 0-1\n";
+    let games: Vec<_> = PgnReader::new(pgn.as_bytes()).map(Result::unwrap).collect();
+
+    let events: Vec<_> = games.iter().map(|game| game.tag("Event")).collect();
+    assert_eq!(events, [Some("a"), Some("b"), Some("c")]);
+    assert_eq!(games[0].result, PgnResult::Unknown);
+    assert_eq!(games[0].moves.len(), 2);
+    assert_eq!(games[1].result, PgnResult::WhiteWins);
+    assert_eq!(games[2].result, PgnResult::BlackWins);
+
+    // Inside a variation the game is broken, but the next one is still read.
+    let pgn = "[Event \"a\"]\n\n1. e4 (1. d4\n[Event \"b\"]\n\n1. d4 d5 1-0\n";
+    let mut reader = PgnReader::new(pgn.as_bytes());
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(err.kind, PgnErrorKind::UnmatchedParenthesis);
+    let game = reader.next().unwrap().unwrap();
+    assert_eq!(game.tag("Event"), Some("b"));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn correctness_pgn_write_round_trip() {
+    for game in PgnReader::new(GAMES.as_bytes()) {
+        let game = game.unwrap();
+        let pgn = game.to_string();
+        assert!(pgn.lines().all(|line| line.len() <= 80), "{pgn}");
+        assert_eq!(pgn.parse::<PgnGame>().unwrap(), game, "{pgn}");
+    }
+}
+
+#[test]
+fn correctness_pgn_write() {
+    let game: PgnGame = r#"[White "Me"]
+[Annotator "Someone"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12"]
+
+12... Kd7 {A long comment that will have to be wrapped somewhere in the middle}
+13. e4 $2 (13. e3 Ke6) 13... Ke6 *"#
+        .parse()
+        .unwrap();
+
+    let expected = r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "Me"]
+[Black "?"]
+[Result "*"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12"]
+[Annotator "Someone"]
+
+12... Kd7 {A long comment that will have to be wrapped somewhere in the middle}
+13. e4 $2 (13. e3 Ke6) 13... Ke6 *
+"#;
+    assert_eq!(game.to_string(), expected);
+
+    let options = PgnWriteOptions {
+        line_width: 20,
+        comments: false,
+        variations: false,
+        ..Default::default()
+    };
+    let pgn = game.to_pgn(&options);
+    let movetext: Vec<_> = pgn.lines().skip(11).collect();
+    assert_eq!(movetext, ["12... Kd7 13. e4 $2", "Ke6 *"]);
+}
+
+#[test]
+fn correctness_pgn_write_canonical_san() {
+    let game: PgnGame = "1. e4 f6 2. Qh5 g6 3. Ngf3 gxh5 4. Bc4 a6 5. 0-0 (5. Nc3 a5) *"
+        .parse()
+        .unwrap();
+    assert_eq!(game.moves[2].san, "Qh5");
+
+    assert_eq!(
+        game.to_string().lines().last(),
+        Some("1. e4 f6 2. Qh5+ g6 3. Nf3 gxh5 4. Bc4 a6 5. O-O (5. Nc3 a5) *")
+    );
+}
+
+#[test]
+fn correctness_pgn_write_comment_with_brace() {
+    let mut game: PgnGame = "1. e4 e5 2. Nf3 *".parse().unwrap();
+    game.moves[0].comments.push("Not } the end".to_string());
+    game.moves[1].comments.push("}".to_string());
+
+    let pgn = game.to_string();
+    assert_eq!(
+        pgn.lines().last(),
+        Some("1. e4 {Not the end} 1... e5 {} 2. Nf3 *")
+    );
+
+    let read: PgnGame = pgn.parse().unwrap();
+    assert_eq!(read.moves.len(), 3);
+    assert_eq!(read.moves[0].comments, ["Not the end"]);
+    assert_eq!(read.moves[1].comments, [""]);
+    assert_eq!(read.moves[2].san, "Nf3");
+}
+
+#[test]
+fn correctness_pgn_write_played_game() {
+    let mut game = PgnGame::new(Board::default());
+    game.set_tag("Event", "Casual");
+    for movement in ["f2f3", "e5", "g4", "Qh4"] {
+        game.push(movement).unwrap();
+    }
+    game.push("e4").unwrap_err();
+
+    assert_eq!(game.result, PgnResult::BlackWins);
+    assert_eq!(
+        game.to_string().lines().last(),
+        Some("1. f3 e5 2. g4 Qh4# 0-1")
+    );
+}
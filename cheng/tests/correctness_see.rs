@@ -0,0 +1,64 @@
+use cheng::{BorkedBoard, FromIntoFen, PseudoMove};
+
+fn see(fen: &str, movement: &str) -> i32 {
+    let board = BorkedBoard::from_fen(fen).unwrap();
+    board.see(&movement.parse::<PseudoMove>().unwrap())
+}
+
+#[test]
+fn test_see_simple_exchanges() {
+    // Undefended pawn.
+    assert_eq!(
+        see("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1e5"),
+        100
+    );
+    // Rook takes a pawn defended by a pawn.
+    assert_eq!(see("4k3/8/3p4/4p3/8/8/8/4RK2 w - - 0 1", "e1e5"), -400);
+    // Quiet move to an attacked square.
+    assert_eq!(see("4k3/8/3p4/8/8/8/8/2N1K3 w - - 0 1", "c1e2"), 0);
+    assert_eq!(see("4k3/8/3p4/8/8/8/8/1N2K3 w - - 0 1", "b1c3"), 0);
+    assert_eq!(see("4k3/8/8/3p4/8/8/8/1N2K3 w - - 0 1", "b1c3"), 0);
+    assert_eq!(see("4k3/8/8/3p4/8/8/8/3NK3 w - - 0 1", "d1c3"), 0);
+    assert_eq!(see("4k3/8/8/8/3p4/8/8/3NK3 w - - 0 1", "d1c3"), -300);
+}
+
+#[test]
+fn test_see_x_rays() {
+    // https://www.chessprogramming.org/SEE_-_The_Swap_Algorithm
+    assert_eq!(
+        see(
+            "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+            "d3e5"
+        ),
+        -200
+    );
+    // The pieces behind each other join the exchange.
+    assert_eq!(see("3rk3/8/8/3p4/8/8/3R4/3QK3 w - - 0 1", "d2d5"), 100);
+    assert_eq!(see("3rk3/3r4/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5"), -400);
+}
+
+#[test]
+fn test_see_special_moves() {
+    // En passant.
+    assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), 100);
+    // Promotion that gets captured.
+    assert_eq!(see("3r3k/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8q"), 800 - 900);
+    assert_eq!(see("3r3k/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7d8q"), 500 + 800);
+    assert_eq!(
+        see("3rr2k/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7d8q"),
+        500 + 800 - 900
+    );
+    // The king can only recapture the last attacker.
+    assert_eq!(see("3r3k/8/8/3p4/4K3/8/8/3R4 w - - 0 1", "d1d5"), 100);
+    assert_eq!(see("3r3k/3r4/8/3p4/4K3/8/8/3R4 w - - 0 1", "d1d5"), -400);
+    // Castles.
+    assert_eq!(see("4k3/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1"), 0);
+}
+
+#[test]
+fn test_see_ge() {
+    let board = BorkedBoard::from_fen("4k3/8/3p4/4p3/8/8/8/4RK2 w - - 0 1").unwrap();
+    let movement = "e1e5".parse().unwrap();
+    assert!(board.see_ge(&movement, -400));
+    assert!(!board.see_ge(&movement, 0));
+}
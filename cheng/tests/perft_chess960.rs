@@ -0,0 +1,74 @@
+use cheng::{Board, FromIntoFen};
+
+// https://www.chessprogramming.org/Chess960_Perft_Results
+const POSITIONS: [(&str, [usize; 4]); 11] = [
+    (
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        [21, 528, 12_189, 326_672],
+    ),
+    (
+        "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+        [21, 807, 18_002, 667_366],
+    ),
+    (
+        "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+        [20, 479, 10_471, 273_318],
+    ),
+    (
+        "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9",
+        [22, 593, 13_440, 382_958],
+    ),
+    (
+        "1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9",
+        [28, 1_120, 31_058, 1_171_749],
+    ),
+    (
+        "qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9",
+        [29, 899, 26_578, 824_055],
+    ),
+    (
+        "r1k1r2q/p1ppp1pp/8/8/8/8/P1PPP1PP/R1K1R2Q w KQkq - 0 1",
+        [23, 522, 12_333, 285_754],
+    ),
+    (
+        "r1k2r1q/p1ppp1pp/8/8/8/8/P1PPP1PP/R1K2R1Q w KQkq - 0 1",
+        [28, 738, 20_218, 541_480],
+    ),
+    (
+        "8/8/8/4B2b/6nN/8/5P2/2R1K2k w Q - 0 1",
+        [34, 318, 9_002, 118_388],
+    ),
+    (
+        "2r5/8/8/8/8/8/6PP/k2KR3 w K - 0 1",
+        [17, 242, 3_931, 57_700],
+    ),
+    (
+        "4r3/3k4/8/8/8/8/6PP/qR1K1R2 w KQ - 0 1",
+        [19, 628, 12_858, 405_636],
+    ),
+];
+
+#[test]
+fn test_chess960_perft_1_to_3() {
+    for (fen, nodes) in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        assert!(board.is_chess960(), "{fen}");
+        for (depth, &expected) in nodes.iter().enumerate().take(3) {
+            assert_eq!(
+                board.perft(depth + 1),
+                expected,
+                "{fen} at depth {}",
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+#[ignore = "expensive"]
+fn test_chess960_perft_4() {
+    for (fen, nodes) in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(board.perft(4), nodes[3], "{fen}");
+    }
+}
@@ -1,17 +1,71 @@
-use cheng::{Board, FromIntoFen};
+use std::sync::OnceLock;
+
+use cheng::{Epd, Perft};
+
+const POSITIONS: &str = include_str!("perft_en_masse.epd");
+
+/// Shared by all the tests, so positions reached from several of them are
+/// only counted once.
+fn perft() -> &'static Perft {
+    static PERFT: OnceLock<Perft> = OnceLock::new();
+    PERFT.get_or_init(Perft::default)
+}
+
+/// Checks the `D3` of the positions in the `batch`th hundred.
+fn perft_batch(batch: usize) {
+    for line in POSITIONS.lines().skip(batch * 100).take(100) {
+        let epd: Epd = line.parse().unwrap();
+        let expected = epd.perft(3).unwrap();
+        assert_eq!(perft().perft(epd.board.inner(), 3), expected, "{line}");
+    }
+}
 
 macro_rules! perft {
-    ($perft_name:ident; $($fen:expr => $nodes:expr),* $(,)?) => {
-        #[ignore]
-        #[test]
-        fn $perft_name() {
-            cheng::init();
-            $(
-                let board = Board::from_fen($fen).unwrap();
-                assert_eq!(board.perft(3), $nodes);
-            )*
-        }
+    ($($perft_name:ident => $batch:expr),* $(,)?) => {
+        $(
+            #[ignore]
+            #[test]
+            fn $perft_name() {
+                perft_batch($batch);
+            }
+        )*
     };
 }
 
-include!("perft_en_masse.txt");
+perft!(
+    perft_batch_00 => 0,
+    perft_batch_01 => 1,
+    perft_batch_02 => 2,
+    perft_batch_03 => 3,
+    perft_batch_04 => 4,
+    perft_batch_05 => 5,
+    perft_batch_06 => 6,
+    perft_batch_07 => 7,
+    perft_batch_08 => 8,
+    perft_batch_09 => 9,
+    perft_batch_10 => 10,
+    perft_batch_11 => 11,
+    perft_batch_12 => 12,
+    perft_batch_13 => 13,
+    perft_batch_14 => 14,
+    perft_batch_15 => 15,
+    perft_batch_16 => 16,
+    perft_batch_17 => 17,
+    perft_batch_18 => 18,
+    perft_batch_19 => 19,
+    perft_batch_20 => 20,
+    perft_batch_21 => 21,
+    perft_batch_22 => 22,
+    perft_batch_23 => 23,
+    perft_batch_24 => 24,
+    perft_batch_25 => 25,
+    perft_batch_26 => 26,
+    perft_batch_27 => 27,
+    perft_batch_28 => 28,
+    perft_batch_29 => 29,
+);
+
+#[test]
+fn perft_en_masse_has_every_batch() {
+    assert_eq!(POSITIONS.lines().count(), 30 * 100);
+}
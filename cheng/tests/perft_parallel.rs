@@ -0,0 +1,76 @@
+use std::ops::ControlFlow;
+
+use cheng::{BorkedBoard, FromIntoFen, Perft};
+
+const POSITIONS: [&str; 4] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    // https://www.chessprogramming.org/Perft_Results
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+];
+
+#[test]
+fn test_perft_driver_matches_perft() {
+    let drivers = [Perft::new(1, 0), Perft::new(4, 0), Perft::new(4, 1 << 20)];
+
+    for fen in POSITIONS {
+        let mut board = BorkedBoard::from_fen(fen).unwrap();
+        for depth in 0..=3 {
+            let expected = board.perft(depth);
+            for perft in &drivers {
+                assert_eq!(perft.perft(&board, depth), expected, "{fen} at {depth}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_perft_divide() {
+    let mut board = BorkedBoard::from_fen(POSITIONS[1]).unwrap();
+    let perft = Perft::new(4, 1 << 20);
+
+    let divide = perft.divide(&board, 3);
+    let moves = board.legal_moves();
+    assert_eq!(divide.len(), moves.len());
+
+    for ((movement, nodes), &expected) in divide.into_iter().zip(moves.iter()) {
+        assert_eq!(movement, expected);
+
+        let undo = board.make(&movement.into());
+        assert_eq!(nodes, board.perft(2), "{movement}");
+        board.unmake(undo);
+    }
+}
+
+#[test]
+fn test_perft_divide_with_stops_at_break() {
+    let board = BorkedBoard::from_fen(POSITIONS[1]).unwrap();
+    let perft = Perft::new(4, 0);
+    let divide = perft.divide(&board, 2);
+
+    let mut seen = Vec::new();
+    let control = perft.divide_with(&board, 2, |movement, nodes| {
+        seen.push((movement, nodes));
+        if seen.len() == 5 {
+            ControlFlow::Break(movement)
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+
+    assert_eq!(control, ControlFlow::Break(divide[4].0));
+    assert_eq!(seen, divide[..5]);
+}
+
+#[test]
+fn test_perft_repeated_runs_and_clear() {
+    let board = BorkedBoard::from_fen(POSITIONS[0]).unwrap();
+    let perft = Perft::new(2, 1 << 20);
+
+    assert_eq!(perft.perft(&board, 4), 197_281);
+    assert_eq!(perft.perft(&board, 4), 197_281);
+
+    perft.clear();
+    assert_eq!(perft.perft(&board, 5), 4_865_609);
+}
@@ -2,7 +2,6 @@ use cheng::Board;
 
 #[test]
 fn test_perft_initial_position_0_to_4() {
-    cheng::init();
     let board = Board::default();
     assert_eq!(board.perft(0), 1);
     assert_eq!(board.perft(1), 20);
@@ -13,7 +12,6 @@ fn test_perft_initial_position_0_to_4() {
 
 #[test]
 fn test_perft_initial_position_5() {
-    cheng::init();
     let board = Board::default();
     assert_eq!(board.perft(5), 4_865_609);
 }
@@ -21,7 +19,6 @@ fn test_perft_initial_position_5() {
 #[test]
 #[ignore = "expensive"]
 fn test_perft_initial_position_6() {
-    cheng::init();
     let board = Board::default();
     assert_eq!(board.perft(6), 119_060_324);
 }
@@ -0,0 +1,95 @@
+use cheng::{BorkedBoard, FromIntoFen, GenerationMode, PackedMove, PseudoMoveGenerator};
+
+const POSITIONS: [&str; 7] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    // https://www.chessprogramming.org/Perft_Results
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    // https://www.chessprogramming.org/Chess960_Perft_Results
+    "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+];
+
+fn legal_moves_in(board: &mut BorkedBoard, mode: GenerationMode) -> Vec<PackedMove> {
+    let gen = PseudoMoveGenerator::with_mode(board, board.turn, mode);
+    let moves: Vec<_> = gen.cached_moves.iter().copied().collect();
+
+    moves
+        .into_iter()
+        .filter(|&movement| {
+            let undo = board.make(&movement.into());
+            let legal = !board.is_borked();
+            board.unmake(undo);
+            legal
+        })
+        .collect()
+}
+
+fn staged_perft(board: &mut BorkedBoard, depth: usize) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+
+    let stages: &[GenerationMode] = if board.side(board.turn).king_in_check {
+        &[GenerationMode::Evasions]
+    } else {
+        &[GenerationMode::Captures, GenerationMode::Quiets]
+    };
+
+    let mut nodes = 0;
+    for &mode in stages {
+        for movement in legal_moves_in(board, mode) {
+            let undo = board.make(&movement.into());
+            nodes += staged_perft(board, depth - 1);
+            board.unmake(undo);
+        }
+    }
+
+    nodes
+}
+
+fn assert_quiet_checks(board: &mut BorkedBoard, depth: usize) {
+    if depth == 0 {
+        return;
+    }
+
+    let mut expected: Vec<_> = legal_moves_in(board, GenerationMode::Quiets)
+        .into_iter()
+        .filter(|&movement| {
+            let undo = board.make(&movement.into());
+            let check = board.side(board.turn).king_in_check;
+            board.unmake(undo);
+            check
+        })
+        .collect();
+    let mut quiet_checks = legal_moves_in(board, GenerationMode::QuietChecks);
+
+    expected.sort_by_key(|movement| movement.to_string());
+    quiet_checks.sort_by_key(|movement| movement.to_string());
+    assert_eq!(quiet_checks, expected, "{}", board.as_fen());
+
+    for movement in legal_moves_in(board, GenerationMode::All) {
+        let undo = board.make(&movement.into());
+        assert_quiet_checks(board, depth - 1);
+        board.unmake(undo);
+    }
+}
+
+#[test]
+fn test_staged_perft_matches_perft() {
+    for fen in POSITIONS {
+        let mut board = BorkedBoard::from_fen(fen).unwrap();
+        let expected = board.perft(3);
+        assert_eq!(staged_perft(&mut board, 3), expected, "{fen}");
+    }
+}
+
+#[test]
+fn test_quiet_checks_are_the_quiet_moves_giving_check() {
+    for fen in POSITIONS {
+        let mut board = BorkedBoard::from_fen(fen).unwrap();
+        assert_quiet_checks(&mut board, 2);
+    }
+}
@@ -0,0 +1,117 @@
+use cheng::{Board, FromIntoFen, Perft, PerftStats};
+
+/// A row of the tables of https://www.chessprogramming.org/Perft_Results.
+#[allow(clippy::too_many_arguments)]
+const fn stats(
+    nodes: usize,
+    captures: usize,
+    en_passant: usize,
+    castles: usize,
+    promotions: usize,
+    checks: usize,
+    discovered_checks: usize,
+    double_checks: usize,
+    checkmates: usize,
+) -> PerftStats {
+    PerftStats {
+        nodes,
+        captures,
+        en_passant,
+        castles,
+        promotions,
+        checks,
+        discovered_checks,
+        double_checks,
+        checkmates,
+    }
+}
+
+fn assert_stats(fen: &str, expected: &[PerftStats]) {
+    let board = Board::from_fen(fen).unwrap();
+    let perft = Perft::default();
+
+    for (depth, expected) in expected.iter().enumerate() {
+        let depth = depth + 1;
+        assert_eq!(board.perft_stats(depth), *expected, "{fen} at {depth}");
+        assert_eq!(
+            perft.perft_stats(board.inner(), depth),
+            *expected,
+            "{fen} at {depth}"
+        );
+    }
+}
+
+#[test]
+fn test_stats_initial_position() {
+    assert_stats(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        &[
+            stats(20, 0, 0, 0, 0, 0, 0, 0, 0),
+            stats(400, 0, 0, 0, 0, 0, 0, 0, 0),
+            stats(8_902, 34, 0, 0, 0, 12, 0, 0, 0),
+            stats(197_281, 1_576, 0, 0, 0, 469, 0, 0, 8),
+        ],
+    );
+}
+
+#[test]
+fn test_stats_kiwipete() {
+    assert_stats(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        &[
+            stats(48, 8, 0, 2, 0, 0, 0, 0, 0),
+            stats(2_039, 351, 1, 91, 0, 3, 0, 0, 0),
+            stats(97_862, 17_102, 45, 3_162, 0, 993, 0, 0, 1),
+        ],
+    );
+}
+
+#[test]
+fn test_stats_position_3() {
+    assert_stats(
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        &[
+            stats(14, 1, 0, 0, 0, 2, 0, 0, 0),
+            stats(191, 14, 0, 0, 0, 10, 0, 0, 0),
+            stats(2_812, 209, 2, 0, 0, 267, 3, 0, 0),
+            stats(43_238, 3_348, 123, 0, 0, 1_680, 106, 0, 17),
+        ],
+    );
+}
+
+#[test]
+fn test_stats_position_4() {
+    assert_stats(
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        &[
+            stats(6, 0, 0, 0, 0, 0, 0, 0, 0),
+            stats(264, 87, 0, 6, 48, 10, 0, 0, 0),
+        ],
+    );
+}
+
+#[test]
+#[ignore = "expensive"]
+fn test_stats_deep() {
+    assert_stats(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        &[
+            stats(48, 8, 0, 2, 0, 0, 0, 0, 0),
+            stats(2_039, 351, 1, 91, 0, 3, 0, 0, 0),
+            stats(97_862, 17_102, 45, 3_162, 0, 993, 0, 0, 1),
+            stats(
+                4_085_603, 757_163, 1_929, 128_013, 15_172, 25_523, 42, 6, 43,
+            ),
+        ],
+    );
+    assert_stats(
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        &[
+            stats(14, 1, 0, 0, 0, 2, 0, 0, 0),
+            stats(191, 14, 0, 0, 0, 10, 0, 0, 0),
+            stats(2_812, 209, 2, 0, 0, 267, 3, 0, 0),
+            stats(43_238, 3_348, 123, 0, 0, 1_680, 106, 0, 17),
+            stats(674_624, 52_051, 1_165, 0, 0, 52_950, 1_292, 3, 0),
+        ],
+    );
+}
@@ -2,8 +2,6 @@ use cheng::{Board, FromIntoFen};
 
 #[test]
 fn perft_castling() {
-    cheng::init();
-
     // https://lichess.org/analysis/4k3/8/8/8/8/8/8/Rb2K2R_w_KQ_-_0_1?color=white
     let board = Board::from_fen("4k3/8/8/8/8/8/8/Rb2K2R w KQ - 0 1").unwrap();
 
@@ -17,7 +15,6 @@ fn perft_castling() {
 #[test]
 fn perft_promotion() {
     // https://lichess.org/analysis/4k3/P7/8/8/8/8/8/4K3_w_-_-_0_1
-    cheng::init();
     let board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
     assert_eq!(board.perft(3), 500);
     assert_eq!(board.perft(4), 2_994);
@@ -27,7 +24,6 @@ fn perft_promotion() {
 #[test]
 fn perft_promotion_both_sides_can_promote() {
     // https://lichess.org/analysis/4k3/PP6/8/8/8/8/p7/4K3_w_-_-_0_1
-    cheng::init();
     let board = Board::from_fen("4k3/PP6/8/8/8/8/p7/4K3 w - - 0 1").unwrap();
     assert_eq!(board.perft(6), 2_066_895);
 }
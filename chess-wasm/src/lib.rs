@@ -1,17 +1,23 @@
+use std::ptr::{addr_of, addr_of_mut};
+
 use flimsybird::Evaluable;
 use js_sys::JsString;
 use wasm_bindgen::prelude::*;
 
-use cheng::{Board, FromIntoFen, GameResult, MoveKind, Piece, PseudoMove, Side, SidedPiece};
+use cheng::{Board, FromIntoFen, Game, GameResult, MoveKind, Piece, PseudoMove, Side, SidedPiece};
 
-static mut BOARD: Option<Board> = None;
+static mut GAME: Option<Game> = None;
 
-fn get_board() -> &'static Board {
-    unsafe { BOARD.as_ref() }.expect("BOARD was not initialized")
+fn get_game() -> &'static Game {
+    unsafe { (*addr_of!(GAME)).as_ref() }.expect("GAME was not initialized")
 }
 
-fn get_board_mut() -> &'static mut Board {
-    unsafe { BOARD.as_mut() }.expect("BOARD was not initialized")
+fn get_game_mut() -> &'static mut Game {
+    unsafe { (*addr_of_mut!(GAME)).as_mut() }.expect("GAME was not initialized")
+}
+
+fn get_board() -> &'static Board {
+    get_game().board()
 }
 
 fn side_to_js_string(side: Side) -> JsString {
@@ -24,29 +30,29 @@ fn side_to_js_string(side: Side) -> JsString {
 #[wasm_bindgen(start)]
 pub fn main() {
     wasm_logger::init(wasm_logger::Config::default());
-    cheng::init();
 
     unsafe {
-        BOARD = Some(Board::default());
+        GAME = Some(Game::default());
     }
 }
 
 #[wasm_bindgen(js_name = "restartBoard")]
 pub fn restart_board() {
     unsafe {
-        BOARD = Some(Board::default());
+        GAME = Some(Game::default());
     }
 }
 
 #[wasm_bindgen(js_name = "loadBoardFromFen")]
 pub fn load_board_from_fen(fen: &JsString) -> Result<(), String> {
-    if let Ok(board) = Board::from_fen(fen.as_string().unwrap_or_default().as_ref()) {
-        unsafe {
-            BOARD = Some(board);
-        };
-        Ok(())
-    } else {
-        Err("Invalid FEN".to_string())
+    match Board::from_fen(fen.as_string().unwrap_or_default().as_ref()) {
+        Ok(board) => {
+            unsafe {
+                GAME = Some(Game::new(board));
+            };
+            Ok(())
+        }
+        Err(err) => Err(format!("Invalid FEN: {err:?}")),
     }
 }
 
@@ -65,6 +71,7 @@ pub fn get_side_to_move() -> JsString {
 pub struct GameState {
     pub result: String,
     pub winner: Option<String>,
+    pub reason: Option<String>,
     #[wasm_bindgen(js_name = "kingInCheck")]
     pub king_in_check: bool,
 }
@@ -84,6 +91,10 @@ pub fn get_state() -> GameState {
             GameResult::Checkmate { winner } => Some(format!("{winner:?}")),
             _ => None,
         },
+        reason: match result {
+            GameResult::Draw { reason } => Some(format!("{reason:?}")),
+            _ => None,
+        },
         king_in_check: board.inner().side(board.turn()).king_in_check,
     }
 }
@@ -146,7 +157,7 @@ pub struct MoveFeedback {
 
 #[wasm_bindgen(js_name = "feedMove")]
 pub fn feed_move(movement: &JsString) -> Result<MoveFeedback, String> {
-    let board = get_board_mut();
+    let board = get_board();
     let Some(movement_str) = movement.as_string() else {
         return Err("Argument must be string".to_string());
     };
@@ -177,23 +188,13 @@ pub fn feed_move(movement: &JsString) -> Result<MoveFeedback, String> {
             .occupancy
             .get(movement.destination);
 
-    // TODO: This is code from the feed function. Obviously this is less than ideal.
-    // We should be using a different interface other than LegalMove.
-    let moved_piece_is_king = board
-        .inner()
-        .side(board.turn())
-        .pieces
-        .piece(Piece::King)
-        .get(movement.origin);
-    let castle_side = if moved_piece_is_king {
-        cheng::Castle::move_could_be_castle(board.turn(), &movement)
-    } else {
-        None
-    };
-
+    let castle = board.inner().castle_from_move(&movement);
     let (castle_side, rook_square_before_castle, rook_square_after_castle) =
-        if let Some(castle_side) = castle_side {
-            let rook_square_before_castle = castle_side.rook_square_before_castle(board.turn());
+        if let Some(castle_side) = castle {
+            let rook_square_before_castle = board
+                .inner()
+                .side(board.turn())
+                .castling_rook_square(castle_side);
             let rook_square_after_castle = castle_side.rook_square_after_castle(board.turn());
             (
                 Some(format!("{castle_side:?}")),
@@ -204,9 +205,15 @@ pub fn feed_move(movement: &JsString) -> Result<MoveFeedback, String> {
             (None, None, None)
         };
 
+    // In Chess960 castles are written as the king taking its own rook, but the
+    // king doesn't end up there.
+    let destination = castle.map_or(movement.destination, |castle| {
+        castle.king_square_after_castle(board.turn())
+    });
+
     let move_feedback = MoveFeedback {
         origin: format!("{:?}", movement.origin),
-        destination: format!("{:?}", movement.destination),
+        destination: format!("{destination:?}"),
         promotion: if let MoveKind::Promote(piece) = movement.kind {
             let mut piece = format!("{piece:?}");
             piece.make_ascii_lowercase();
@@ -221,11 +228,55 @@ pub fn feed_move(movement: &JsString) -> Result<MoveFeedback, String> {
         rook_square_after_castle,
     };
 
-    board.try_feed(movement).map_err(|e| format!("{e:?}"))?;
+    get_game_mut()
+        .try_play(movement)
+        .map_err(|e| format!("{e:?}"))?;
 
     Ok(move_feedback)
 }
 
+#[wasm_bindgen(js_name = "undoMove")]
+#[must_use]
+pub fn undo_move() -> bool {
+    get_game_mut().undo()
+}
+
+#[wasm_bindgen(js_name = "redoMove")]
+#[must_use]
+pub fn redo_move() -> bool {
+    get_game_mut().redo()
+}
+
+#[wasm_bindgen(js_name = "gotoPly")]
+#[must_use]
+pub fn goto_ply(ply: usize) -> bool {
+    get_game_mut().goto(ply)
+}
+
+#[wasm_bindgen(js_name = "getPly")]
+#[must_use]
+pub fn get_ply() -> usize {
+    get_game().ply()
+}
+
+#[wasm_bindgen(js_name = "getMoves")]
+#[must_use]
+pub fn get_moves() -> js_sys::Array {
+    let result = js_sys::Array::default();
+
+    for san in get_game().san_moves() {
+        result.push(&JsString::from(san));
+    }
+
+    result
+}
+
+#[wasm_bindgen(js_name = "gameToPgn")]
+#[must_use]
+pub fn game_to_pgn() -> JsString {
+    JsString::from(get_game().to_pgn().to_string())
+}
+
 #[wasm_bindgen(js_name = "validMoves")]
 #[must_use]
 pub fn valid_moves() -> js_sys::Array {
@@ -243,15 +294,15 @@ pub fn valid_moves() -> js_sys::Array {
 #[wasm_bindgen]
 #[must_use]
 pub fn evaluate() -> i32 {
-    let board = get_board_mut();
+    let mut board = get_board().clone();
 
-    Evaluable::evaluate(board).1 .0
+    Evaluable::evaluate(&mut board).1 .0
 }
 
 #[wasm_bindgen(js_name = "flimsybirdRun")]
 pub async fn flimsybird_run() -> Result<String, String> {
-    let board = get_board_mut();
-    let (Some(best_move), ev) = Evaluable::evaluate(board) else {
+    let mut board = get_board().clone();
+    let (Some(best_move), ev) = Evaluable::evaluate(&mut board) else {
         return Err("No move is possible".to_string());
     };
 
@@ -263,8 +314,7 @@ pub async fn flimsybird_run() -> Result<String, String> {
 
 #[wasm_bindgen(js_name = "static_evaluate")]
 pub async fn static_evaluate() -> Result<(), String> {
-    let board = get_board_mut();
-    flimsybird::board_static_evaluation::<flimsybird::LogTracer>(board);
+    flimsybird::board_static_evaluation::<flimsybird::LogTracer>(get_board());
 
     Ok(())
 }
@@ -8,32 +8,36 @@ pub use static_evaluation_tracer::{LogTracer, NoopTracer, StaticEvaluationTracer
 use std::convert::TryFrom;
 
 use cheng::{
-    prelude as sq, Board, BorkedBoard, GameResult, LegalMove, MoveKind, Piece, PseudoMoveGenerator,
-    Side, SidedPiece,
+    prelude as sq, Board, BorkedBoard, GameResult, GenerationMode, LegalMove, MoveKind, Piece,
+    PseudoMove, PseudoMoveGenerator, Side, SidedPiece,
 };
 
 pub static mut EVALUATED_NODES: usize = 0;
 pub trait Evaluable {
-    fn evaluate(&mut self) -> (Option<LegalMove>, Evaluation);
+    fn evaluate(&mut self) -> (Option<LegalMove<'_>>, Evaluation);
 }
 
 impl Evaluable for Board {
-    fn evaluate(&mut self) -> (Option<LegalMove>, Evaluation) {
+    fn evaluate(&mut self) -> (Option<LegalMove<'_>>, Evaluation) {
         unsafe { EVALUATED_NODES = 0 }
 
         let max_depth = params::DEPTH;
         let best_i_can_do = Evaluation::winner(self.turn().opposite());
         let best_o_can_do = Evaluation::winner(self.turn());
-        board_rec_evaluate(self.inner(), max_depth, best_i_can_do, best_o_can_do)
+        let mut board = self.inner().clone();
+        let (best_move, evaluation) =
+            board_rec_evaluate(&mut board, max_depth, best_i_can_do, best_o_can_do);
+        let best_move = best_move.map(|mv| unsafe { LegalMove::unchecked_new(mv, self.inner()) });
+        (best_move, evaluation)
     }
 }
 
 fn board_rec_evaluate(
-    board: &BorkedBoard,
+    board: &mut BorkedBoard,
     depth: u8,
     mut best_i_can_do: Evaluation,
     best_o_can_do: Evaluation,
-) -> (Option<LegalMove>, Evaluation) {
+) -> (Option<PseudoMove>, Evaluation) {
     if depth == 0 {
         let board = Board::try_from(board.clone()).unwrap();
         return (
@@ -48,46 +52,54 @@ fn board_rec_evaluate(
     }
 
     let mut best_move = None;
-    let opposite = board.side(board.turn.opposite()).occupancy;
-    let opposite_pieces = &board.side(board.turn.opposite()).pieces;
-
-    let mut moves = board.moves();
-    moves.cached_moves.sort_unstable_by_key(|mv| {
-        let move_is_capture_gain = if opposite.get(mv.destination) {
-            params::piece_value(opposite_pieces.find(mv.destination).unwrap())
-        } else {
-            0
-        };
-        let movekind_gain = match mv.kind {
-            MoveKind::Castle(_) => 50,
-            MoveKind::Promote(Piece::Queen) => 150,
-            MoveKind::Promote(_) => 120,
-            _ => 0,
-        };
-        -move_is_capture_gain - movekind_gain
-    });
-
-    for movement in moves {
-        let mut board_clone = board.clone();
-        board_clone.feed_unchecked(&movement);
-        if board_clone.is_borked() {
-            continue;
-        }
 
-        let (_, new_ev) = board_rec_evaluate(&board_clone, depth - 1, best_o_can_do, best_i_can_do);
+    // Captures come first, so a cutoff among them saves generating the quiet
+    // moves.
+    let stages: &[GenerationMode] = if board.side(board.turn).king_in_check {
+        &[GenerationMode::Evasions]
+    } else {
+        &[GenerationMode::Captures, GenerationMode::Quiets]
+    };
 
-        if new_ev.is_better_than(board.turn, best_o_can_do) {
-            break;
-        } else if new_ev.is_better_than(board.turn, best_i_can_do) || best_move.is_none() {
-            best_move = Some(movement);
-            best_i_can_do = new_ev;
+    let turn = board.turn;
+    'stages: for &mode in stages {
+        let mut moves = PseudoMoveGenerator::with_mode(board, turn, mode).cached_moves;
+        moves.sort_by_cached_key(|mv| {
+            // Winning captures and promotions first, losing ones last.
+            let exchange_gain = if mv.is_capture() || mv.promotion_piece().is_some() {
+                board.see(&PseudoMove::from(*mv))
+            } else {
+                0
+            };
+            let movekind_gain = match mv.kind() {
+                MoveKind::Castle(_) => 50,
+                _ => 0,
+            };
+            -exchange_gain - movekind_gain
+        });
+
+        for movement in moves {
+            let undo = board.make_packed(movement);
+            if board.is_borked() {
+                board.unmake(undo);
+                continue;
+            }
+
+            let (_, new_ev) = board_rec_evaluate(board, depth - 1, best_o_can_do, best_i_can_do);
+            board.unmake(undo);
+
+            if new_ev.is_better_than(turn, best_o_can_do) {
+                break 'stages;
+            } else if new_ev.is_better_than(turn, best_i_can_do) || best_move.is_none() {
+                best_move = Some(PseudoMove::from(movement));
+                best_i_can_do = new_ev;
+            }
         }
     }
 
-    if let Some(best_move) = best_move {
-        let best_move = unsafe { LegalMove::unchecked_new(best_move, board) };
+    if best_move.is_some() {
         best_i_can_do.push();
-        (Some(best_move), best_i_can_do)
+        (best_move, best_i_can_do)
     } else {
         // We don't have to do quiescense search here, *we are here because there are no possible moves*.
         let board = Board::try_from(board.clone()).unwrap();
@@ -106,12 +118,13 @@ pub fn quiescense_search(
     }
 
     let bb = board.inner();
-    let opposite = bb.side(bb.turn.opposite()).occupancy;
 
-    for movement in bb.moves() {
-        if !opposite.get(movement.destination) {
+    for movement in PseudoMoveGenerator::with_mode(bb, bb.turn, GenerationMode::Captures) {
+        // Captures that lose material can't raise the evaluation.
+        if !bb.see_ge(&movement, 0) {
             continue;
         }
+
         let mut board_clone = bb.clone();
         board_clone.feed_unchecked(&movement);
 
@@ -129,7 +142,7 @@ pub fn quiescense_search(
         }
     }
 
-    return best_i_can_do;
+    best_i_can_do
 }
 
 pub fn board_static_evaluation<L>(board: &Board) -> Evaluation
@@ -139,8 +152,11 @@ where
     unsafe { EVALUATED_NODES += 1 }
 
     match board.result() {
+        // The fifty-move rule must be claimed, but an engine should expect
+        // the other side to do it.
+        GameResult::Undecided if board.inner().halfmove_clock >= 100 => return Evaluation::DRAW,
         GameResult::Undecided => {}
-        GameResult::Draw => return Evaluation::DRAW,
+        GameResult::Draw { .. } => return Evaluation::DRAW,
         GameResult::Checkmate { winner } => return Evaluation::winner(winner),
     }
 
@@ -13,7 +13,6 @@ fn run(fen: &str) -> PseudoMove {
 
 #[test]
 fn absurd_dont_yeet_rook() {
-    cheng::init();
     let bm = run("8/3P4/8/4R1P1/5PK1/3r4/5k2/8 w - - 3 49");
     assert_ne!(bm, "e5e2".try_into().unwrap());
 
@@ -12,7 +12,6 @@ fn assert_wins(side: Side, fen: &str) {
 
 #[test]
 fn checkmate_in_one() {
-    cheng::init();
     // https://lichess.org/analysis/5k2/8/8/4q3/8/3K4/1q6/8_b_-_-_0_1?color=white
     assert_wins(Side::Black, "5k2/8/8/4q3/8/3K4/1q6/8 b - - 0 1");
 
@@ -0,0 +1,18 @@
+use cheng::{Board, FromIntoFen};
+use flimsybird::{board_static_evaluation, Evaluation, NoopTracer};
+
+#[test]
+fn fifty_move_rule_is_a_draw() {
+    // A queen up, but the fifty-move rule can be claimed.
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/Q3K3 b - - 100 80").unwrap();
+    assert_eq!(
+        board_static_evaluation::<NoopTracer>(&board),
+        Evaluation::DRAW
+    );
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/Q3K3 b - - 99 80").unwrap();
+    assert_ne!(
+        board_static_evaluation::<NoopTracer>(&board),
+        Evaluation::DRAW
+    );
+}
@@ -7,8 +7,8 @@ use std::ops;
 pub struct Evaluation(pub i32);
 
 impl Evaluation {
-    pub const BLACK_WIN: Self = Evaluation(std::i32::MIN);
-    pub const WHITE_WIN: Self = Evaluation(std::i32::MAX);
+    pub const BLACK_WIN: Self = Evaluation(i32::MIN);
+    pub const WHITE_WIN: Self = Evaluation(i32::MAX);
     pub const DRAW: Self = Evaluation(0);
 
     const CHECKMATE_NET_SIZE: u32 = 10;
@@ -5,19 +5,19 @@ mod debugger;
 pub use debugger::{Debugger, LogAllDebugger, NoDebugger};
 
 use cheng::Piece;
-use cheng::PseudoMoveGenerator;
 use cheng::Side;
 use cheng::{Board, BorkedBoard, GameResult};
-use cheng::{LegalMove, PseudoMove};
+use cheng::{GenerationMode, PseudoMoveGenerator};
+use cheng::{LegalMove, MoveList, PseudoMove};
 
 use std::time::{Duration, Instant};
 
-pub fn go(board: &Board) -> GoResult {
+pub fn go(board: &Board) -> GoResult<'_> {
     let mut franfish = Franfish::<NoDebugger>::default();
     franfish.go(board)
 }
 
-pub fn go_debug(board: &Board) -> GoResult {
+pub fn go_debug(board: &Board) -> GoResult<'_> {
     let mut franfish = Franfish::new(LogAllDebugger::default(), Some(Duration::from_secs(15)));
     franfish.go(board)
 }
@@ -81,17 +81,15 @@ impl<D: Debugger> Franfish<D> {
         let mut best_eval = Evaluation::wins(board.turn().opposite());
         let mut exit = SearchExit::FullDepth;
 
+        let mut scratch = board.inner().clone();
         for movement in board.moves() {
             let pseudomove = PseudoMove::from(movement.clone());
             self.debugger.on_feed(&pseudomove, EV_DEPTH);
 
-            let mut clone = board.inner().clone();
-            clone.feed_unchecked(&pseudomove);
-            if clone.is_borked() {
-                continue;
-            }
+            let undo = scratch.make(&pseudomove);
+            let result = self.minimax(&mut scratch, EV_DEPTH - 1, alpha, beta);
+            scratch.unmake(undo);
 
-            let result = self.minimax(&clone, EV_DEPTH - 1, alpha, beta);
             if result.exit == SearchExit::Timeout {
                 log::debug!("got timeout :(");
                 exit = SearchExit::Timeout;
@@ -117,9 +115,11 @@ impl<D: Debugger> Franfish<D> {
         }
     }
 
+    // Alpha and beta are only threaded through until pruning is implemented.
+    #[allow(clippy::only_used_in_recursion)]
     fn minimax(
         &mut self,
-        board: &BorkedBoard,
+        board: &mut BorkedBoard,
         depth: usize,
         mut alpha: Evaluation,
         mut beta: Evaluation,
@@ -142,8 +142,8 @@ impl<D: Debugger> Franfish<D> {
             };
         }
 
-        let gen = PseudoMoveGenerator::new(board);
-        if gen.is_empty() {
+        let (moves, losing_captures) = ordered_moves(board);
+        if moves.is_empty() {
             let eval = if board.side(board.turn).king_in_check {
                 Evaluation::wins(board.turn.opposite())
             } else {
@@ -161,18 +161,26 @@ impl<D: Debugger> Franfish<D> {
         let mut best_eval = Evaluation::wins(board.turn.opposite());
         let mut legal_move_exists = false;
 
-        for movement in gen {
-            let mut clone = board.clone();
-            clone.feed_unchecked(&movement);
-            if clone.is_borked() {
+        for (i, movement) in moves.into_iter().enumerate() {
+            // The recapture is past the horizon, so a losing capture would
+            // look like it wins material.
+            if depth == 1 && i >= losing_captures && legal_move_exists {
+                break;
+            }
+
+            let undo = board.make_packed(movement);
+            if board.is_borked() {
+                board.unmake(undo);
                 continue;
             }
 
             legal_move_exists = true;
 
-            self.debugger.on_feed(&movement, depth);
+            self.debugger.on_feed(undo.movement(), depth);
+
+            let result = self.minimax(board, depth - 1, alpha, beta);
+            board.unmake(undo);
 
-            let result = self.minimax(&clone, depth - 1, alpha, beta);
             if board.turn == Side::White && best_eval <= result.eval {
                 alpha = result.eval;
                 best_eval = result.eval;
@@ -195,6 +203,37 @@ impl<D: Debugger> Franfish<D> {
     }
 }
 
+/// The moves of the side to move, with the captures that lose material after
+/// the quiet moves, and the index of the first of them.
+fn ordered_moves(board: &BorkedBoard) -> (MoveList, usize) {
+    let turn = board.turn;
+    let generate = |mode| PseudoMoveGenerator::with_mode(board, turn, mode).cached_moves;
+
+    if board.side(turn).king_in_check {
+        let evasions = generate(GenerationMode::Evasions);
+        let len = evasions.len();
+        return (evasions, len);
+    }
+
+    // Swap the captures that don't lose material to the front.
+    let mut moves = generate(GenerationMode::Captures);
+    let mut winning = 0;
+    for i in 0..moves.len() {
+        if board.see_ge(&PseudoMove::from(moves[i]), 0) {
+            moves.swap(winning, i);
+            winning += 1;
+        }
+    }
+    let losing = moves.len() - winning;
+
+    // Then move the losing ones behind the quiet moves.
+    moves.extend(generate(GenerationMode::Quiets));
+    moves[winning..].rotate_left(losing);
+
+    let first_losing = moves.len() - losing;
+    (moves, first_losing)
+}
+
 fn evaluate(board: &BorkedBoard) -> Evaluation {
     fn piece_value(piece: Piece) -> Evaluation {
         match piece {
@@ -213,8 +252,10 @@ fn evaluate(board: &BorkedBoard) -> Evaluation {
     }
 
     match board.compute_result() {
-        GameResult::Draw => return Evaluation::DRAW,
+        GameResult::Draw { .. } => return Evaluation::DRAW,
         GameResult::Checkmate { winner } => return Evaluation::wins(winner),
+        // The fifty-move rule must be claimed, but the other side will.
+        GameResult::Undecided if board.halfmove_clock >= 100 => return Evaluation::DRAW,
         GameResult::Undecided => {}
     }
 
@@ -8,7 +8,7 @@ verbose() {
 }
 
 help_and_exit() {
-	echo "usage: $0 [--copy] [--low-nbits]"
+	echo "usage: $0 [--copy]"
     exit 1
 }
 
@@ -29,14 +29,13 @@ for flag in $*
 do
     case "$flag" in
         --copy) copy=1 ;;
-        --low-nbits) cargoflags="$cargoflags --features low_nbits" ;;
         *) help_and_exit ;;
     esac
 done
 
 workspace_root=$(git rev-parse --show-toplevel)
 
-verbose wasm-pack build --no-typescript --release --target web $workspace_root/chess-wasm $cargoflags
+verbose wasm-pack build --no-typescript --release --target web $workspace_root/chess-wasm
 
 test -e $workspace_root/web/pkg && rm -r $workspace_root/web/pkg
 
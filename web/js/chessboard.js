@@ -381,3 +381,13 @@ function restartMainBoard() {
     mainBoard.syncToWasm();
     mainBoard.scheduleMove();
 }
+
+function undoMainBoard() {
+    if (wasm.undoMove())
+        mainBoard.syncToWasm();
+}
+
+function redoMainBoard() {
+    if (wasm.redoMove())
+        mainBoard.syncToWasm();
+}